@@ -1,115 +1,669 @@
-use crate::export;
+use crate::{export, json, suggest, theme, width};
 
-#[derive(Debug)]
+/// What to do, the preview is shown when no command is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Play,
+    List,
+    Show,
+    Export,
+    Validate,
+    Info,
+    Completions,
+    Config,
+    Schema,
+    /// Prints the spinner names for the completion scripts.
+    Spinners,
+}
+
+pub struct Subcommand {
+    pub command: Command,
+    pub name: &'static str,
+    /// What can be given after the command(e.g. `<spinner>`).
+    pub operand: Option<&'static str>,
+    pub help: &'static str,
+}
+
+/// Output of `list`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Plain,
+    Tsv,
+    Json,
+}
+
+/// Order of the spinners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sort {
+    /// The order of the files.
+    #[default]
+    File,
+    Name,
+    Frames,
+    Width,
+    /// Recently previewed first.
+    Recent,
+}
+
+impl Sort {
+    /// Name used by `--sort`.
+    pub fn name (&self) -> &'static str {
+        SORT_MODES[*self as usize]
+    }
+
+    /// Sort mode after this one, used to cycle through them.
+    pub fn next (&self) -> Sort {
+        match self {
+            Sort::File => Sort::Name,
+            Sort::Name => Sort::Frames,
+            Sort::Frames => Sort::Width,
+            Sort::Width => Sort::Recent,
+            Sort::Recent => Sort::File,
+        }
+    }
+}
+
+/// Commands, in the order they're shown in the help message. Commands
+/// starting with `__` are hidden.
+pub const COMMANDS: &[Subcommand] = &[
+    Subcommand { command: Command::Play, name: "play", operand: Some("<spinner>"), help: "Previews the spinner, same as giving `no command`." },
+    Subcommand { command: Command::List, name: "list", operand: Some("<pattern>"), help: "Prints the spinners, or the ones with a name matching `<pattern>`." },
+    Subcommand { command: Command::Show, name: "show", operand: Some("<spinner>"), help: "Prints every frame of the spinner in a `strip`." },
+    Subcommand { command: Command::Export, name: "export", operand: Some("<spinner>"), help: "Exports the spinner `without the preview`. See [Export options]." },
+    Subcommand { command: Command::Validate, name: "validate", operand: None, help: "Checks the spinners for `problems`, exits with {1} if any are found." },
+    Subcommand { command: Command::Info, name: "info", operand: Some("<spinner>"), help: "Prints `details` about the spinner." },
+    Subcommand { command: Command::Completions, name: "completions", operand: Some("<shell>"), help: "Prints the completion script for {bash}, {zsh} or {fish}." },
+    Subcommand { command: Command::Config, name: "config", operand: Some("path"), help: "Prints where the `config file` is looked up." },
+    Subcommand { command: Command::Schema, name: "schema", operand: None, help: "Prints the `JSON Schema` of spinner files." },
+    Subcommand { command: Command::Spinners, name: "__spinners", operand: None, help: "" },
+];
+
+/// Shells `completions` supports.
+pub const SHELLS: &[&str] = &[ "bash", "zsh", "fish" ];
+
+pub const COLOR_MODES: &[&str] = &[ "auto", "always", "never" ];
+pub const EXPORT_FORMATS: &[&str] = &[ "list", "array", "string", "l", "a", "s" ];
+pub const LIST_FORMATS: &[&str] = &[ "plain", "tsv", "json" ];
+pub const CONFLICT_POLICIES: &[&str] = &[ "override", "keep-first", "rename", "error" ];
+pub const PAD_ALIGNS: &[&str] = &[ "left", "right", "center" ];
+/// Sort modes, in the order of `Sort`.
+pub const SORT_MODES: &[&str] = &[ "file", "name", "frames", "width", "recent" ];
+
+/// What the completion scripts offer for the value of an argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Complete {
+    Nothing,
+    Choices(&'static [&'static str]),
+    Path,
+    /// Comma separated spinner names.
+    Spinners,
+}
+
+#[derive(Debug, Default)]
 pub struct SpinnConfig {
+    pub command: Option<Command>,
     pub show_help: Option<bool>,
+    pub edit: Option<bool>,
+    pub fullscreen: Option<bool>,
+    /// Reopen the last spinner.
+    pub last: Option<bool>,
     pub export_as: Option<export::SpinnExport>,
     pub multi_line: Option<bool>,
     pub quote: Option<char>,
+    /// Pads the frames to the same width.
+    pub pad: Option<width::Align>,
+    /// Character the frames are padded with.
+    pub fill: Option<char>,
 
-    pub source: Option<String>,
     pub pick: Option<String>,
     pub interval: Option<u32>,
+    pub theme: Option<String>,
+    /// `auto`, `always` or `never`.
+    pub color: Option<String>,
+    /// Spinners to compare side by side.
+    pub compare: Option<Vec<String>>,
+    /// Spinner files & directories, in the order they're read.
+    pub sources: Vec<String>,
+    /// What to do with spinners that have the same name.
+    pub conflicts: Option<json::Conflict>,
+    pub sort: Option<Sort>,
+
+    pub format: Option<Format>,
+    /// Tags every listed spinner should have.
+    pub tags: Vec<String>,
+    /// Text a frame of every listed spinner should contain.
+    pub contains: Option<String>,
+    pub max_width: Option<usize>,
+    /// Fix what `validate` can.
+    pub fix: Option<bool>,
+}
+
+/// What comes after an argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Takes {
+    Nothing,
+    /// A value, given as `--name=value` or `--name value`.
+    Value(&'static str),
+    /// `true` when given alone, or an explicit `true`/`false`.
+    Bool,
+}
+
+pub struct Arg {
+    pub long: &'static str,
+    pub short: Option<char>,
+    pub takes: Takes,
+    /// Shown in the help message. Text in backticks is highlighted,
+    /// `[...]` refers to another section & `{...}` is a value.
+    pub help: &'static str,
+    pub complete: Complete,
+    set: fn(&mut SpinnConfig, &str) -> Result<(), String>,
+}
+
+/// Arguments, in the order they're shown in the help message.
+pub const ARGS: &[Arg] = &[
+    Arg {
+        long: "color",
+        short: None,
+        takes: Takes::Value("<when>"),
+        help: "Whether to use colors, {auto}, {always} or {never}.",
+        complete: Complete::Choices(COLOR_MODES),
+        set: |config, value| {
+            choice(value, COLOR_MODES)?;
+            config.color = Some(value.to_owned());
+            Ok(())
+        },
+    },
+    Arg {
+        long: "compare",
+        short: Some('c'),
+        takes: Takes::Value("<names>"),
+        help: "Comma separated spinners to show `side by side`.",
+        complete: Complete::Spinners,
+        set: |config, value| {
+            config.compare = Some(value.split(',').filter(|n| !n.is_empty()).map(|n| n.to_owned()).collect());
+            Ok(())
+        },
+    },
+    Arg {
+        long: "conflicts",
+        short: None,
+        takes: Takes::Value("<policy>"),
+        help: "What to do with spinners from different files that have the same name, {override}, {keep-first}, {rename} or {error}.",
+        complete: Complete::Choices(CONFLICT_POLICIES),
+        set: |config, value| {
+            config.conflicts = Some(match choice(value, CONFLICT_POLICIES)? {
+                "keep-first" => json::Conflict::KeepFirst,
+                "rename" => json::Conflict::Rename,
+                "error" => json::Conflict::Error,
+                _ => json::Conflict::Override,
+            });
+            Ok(())
+        },
+    },
+    Arg {
+        long: "contains",
+        short: None,
+        takes: Takes::Value("<text>"),
+        help: "Lists the spinners with a frame containing {<text>}.",
+        complete: Complete::Nothing,
+        set: |config, value| {
+            config.contains = Some(value.to_owned());
+            Ok(())
+        },
+    },
+    Arg {
+        long: "edit",
+        short: Some('E'),
+        takes: Takes::Nothing,
+        help: "Opens the spinner in the `editor`.",
+        complete: Complete::Nothing,
+        set: |config, _| {
+            config.edit = Some(true);
+            Ok(())
+        },
+    },
+    Arg {
+        long: "export",
+        short: Some('e'),
+        takes: Takes::Value("<as>"),
+        help: "Export format. See [Export options].",
+        complete: Complete::Choices(EXPORT_FORMATS),
+        set: |config, value| {
+            config.export_as = Some(match choice(value, EXPORT_FORMATS)? {
+                "list" | "l" => export::SpinnExport::List,
+                "array" | "a" => export::SpinnExport::Array,
+                _ => export::SpinnExport::String,
+            });
+            Ok(())
+        },
+    },
+    Arg {
+        long: "fill",
+        short: None,
+        takes: Takes::Value("<char>"),
+        help: "Character `--pad` fills the frames with, a {space} by default.",
+        complete: Complete::Nothing,
+        set: |config, value| {
+            config.fill = Some(value.parse::<char>().map_err(|_| format!("`{}` isn't a single character", value))?);
+            Ok(())
+        },
+    },
+    Arg {
+        long: "fix",
+        short: None,
+        takes: Takes::Nothing,
+        help: "Pads the frames `validate` finds with different widths & saves them.",
+        complete: Complete::Nothing,
+        set: |config, _| {
+            config.fix = Some(true);
+            Ok(())
+        },
+    },
+    Arg {
+        long: "format",
+        short: None,
+        takes: Takes::Value("<as>"),
+        help: "Output of `list`, {plain}, {tsv} or {json}.",
+        complete: Complete::Choices(LIST_FORMATS),
+        set: |config, value| {
+            config.format = Some(match choice(value, LIST_FORMATS)? {
+                "tsv" => Format::Tsv,
+                "json" => Format::Json,
+                _ => Format::Plain,
+            });
+            Ok(())
+        },
+    },
+    Arg {
+        long: "fullscreen",
+        short: Some('f'),
+        takes: Takes::Nothing,
+        help: "Shows the preview in `fullscreen`.",
+        complete: Complete::Nothing,
+        set: |config, _| {
+            config.fullscreen = Some(true);
+            Ok(())
+        },
+    },
+    Arg {
+        long: "help",
+        short: Some('h'),
+        takes: Takes::Nothing,
+        help: "Shows [this message].",
+        complete: Complete::Nothing,
+        set: |config, _| {
+            config.show_help = Some(true);
+            Ok(())
+        },
+    },
+    Arg {
+        long: "interval",
+        short: Some('i'),
+        takes: Takes::Value("<ms>"),
+        help: "Interval between each frame in `miliseconds`.",
+        complete: Complete::Nothing,
+        set: |config, value| {
+            match value.parse::<u32>() {
+                Ok(interval) if interval > 0 => {
+                    config.interval = Some(interval);
+                    Ok(())
+                },
+                _ => Err(format!("`{}` isn't a positive number", value)),
+            }
+        },
+    },
+    Arg {
+        long: "last",
+        short: Some('l'),
+        takes: Takes::Nothing,
+        help: "Reopens the `last spinner` with it's interval.",
+        complete: Complete::Nothing,
+        set: |config, _| {
+            config.last = Some(true);
+            Ok(())
+        },
+    },
+    Arg {
+        long: "max-width",
+        short: None,
+        takes: Takes::Value("<cells>"),
+        help: "Lists the spinners at most {<cells>} wide.",
+        complete: Complete::Nothing,
+        set: |config, value| {
+            config.max_width = Some(value.parse::<usize>().map_err(|_| format!("`{}` isn't a number", value))?);
+            Ok(())
+        },
+    },
+    Arg {
+        long: "multiline",
+        short: Some('m'),
+        takes: Takes::Bool,
+        help: "Whether to export the output in `multiple lines`.",
+        complete: Complete::Choices(&[ "true", "false" ]),
+        set: |config, value| {
+            config.multi_line = Some(value.parse::<bool>().map_err(|_| format!("`{}` isn't `true` or `false`", value))?);
+            Ok(())
+        },
+    },
+    Arg {
+        long: "pad",
+        short: None,
+        takes: Takes::Value("<align>"),
+        help: "Pads the frames to the width of the widest one, aligned to the {left}, {right} or {center}.",
+        complete: Complete::Choices(PAD_ALIGNS),
+        set: |config, value| {
+            config.pad = Some(match choice(value, PAD_ALIGNS)? {
+                "right" => width::Align::Right,
+                "center" => width::Align::Center,
+                _ => width::Align::Left,
+            });
+            Ok(())
+        },
+    },
+    Arg {
+        long: "quote",
+        short: Some('q'),
+        takes: Takes::Value("<char>"),
+        help: "Text to use for `quoting` strings when exporting.",
+        complete: Complete::Choices(&[ "\"", "'", "`" ]),
+        set: |config, value| {
+            config.quote = Some(value.parse::<char>().map_err(|_| format!("`{}` isn't a single character", value))?);
+            Ok(())
+        },
+    },
+    Arg {
+        long: "sort",
+        short: None,
+        takes: Takes::Value("<mode>"),
+        help: "Order of the spinners, {file}(default), {name}, {frames}, {width} or {recent}.",
+        complete: Complete::Choices(SORT_MODES),
+        set: |config, value| {
+            config.sort = Some(match choice(value, SORT_MODES)? {
+                "name" => Sort::Name,
+                "frames" => Sort::Frames,
+                "width" => Sort::Width,
+                "recent" => Sort::Recent,
+                _ => Sort::File,
+            });
+            Ok(())
+        },
+    },
+    Arg {
+        long: "source",
+        short: Some('s'),
+        takes: Takes::Value("<path>"),
+        help: "Path to a `JSON` file containing spinners, or a `directory` of them. Can be repeated.",
+        complete: Complete::Path,
+        set: |config, value| {
+            config.sources.push(value.to_owned());
+            Ok(())
+        },
+    },
+    Arg {
+        long: "tag",
+        short: None,
+        takes: Takes::Value("<tag>"),
+        help: "Lists the spinners tagged with {<tag>}, can be repeated.",
+        complete: Complete::Nothing,
+        set: |config, value| {
+            config.tags.push(value.to_owned());
+            Ok(())
+        },
+    },
+    Arg {
+        long: "theme",
+        short: Some('t'),
+        takes: Takes::Value("<name>"),
+        help: "Color theme to use. See [Themes].",
+        complete: Complete::Choices(theme::THEMES),
+        set: |config, value| {
+            config.theme = Some(value.to_owned());
+            Ok(())
+        },
+    },
+];
+
+/// Returns `value` if it's one of `choices`.
+fn choice<'a> (value: &'a str, choices: &[&str]) -> Result<&'a str, String> {
+    if choices.contains(&value) {
+        return Ok(value);
+    }
+
+    let expected = choices.iter().map(|c| format!("`{}`", c)).collect::<Vec<String>>().join(", ");
+
+    match suggest::closest(value, choices.iter().copied()) {
+        Some(close) => Err(format!("`{}` isn't valid, did you mean `{}`? Expected one of {}", value, close, expected)),
+        None => Err(format!("`{}` isn't valid! Expected one of {}", value, expected)),
+    }
+}
+
+impl Arg {
+    /// `--name=<value>` or `-n=<value>`.
+    pub fn usage (&self, short: bool) -> (String, String) {
+        let name = match self.short {
+            Some(c) if short => format!("-{}", c),
+            _ => format!("--{}", self.long),
+        };
+
+        match self.takes {
+            Takes::Nothing => (name, String::new()),
+            Takes::Value(placeholder) => (name + "=", placeholder.to_owned()),
+            Takes::Bool => (name + "[=", "<bool>]".to_owned()),
+        }
+    }
+}
+
+fn find_long (name: &str) -> Result<&'static Arg, String> {
+    if let Some(arg) = ARGS.iter().find(|a| a.long == name) {
+        return Ok(arg);
+    }
+
+    match suggest::closest(name, ARGS.iter().map(|a| a.long)) {
+        Some(close) => Err(format!("Unknown argument `--{}`, did you mean `--{}`?", name, close)),
+        None => Err(format!("Unknown argument `--{}`! See `--help`.", name)),
+    }
+}
+
+fn find_short (name: &str) -> Result<&'static Arg, String> {
+    let mut chars = name.chars();
+
+    if let (Some(c), None) = (chars.next(), chars.next())
+        && let Some(arg) = ARGS.iter().find(|a| a.short == Some(c)) {
+        return Ok(arg);
+    }
+
+    // `-theme` is probably `--theme`.
+    match ARGS.iter().find(|a| a.long == name) {
+        Some(arg) => Err(format!("Unknown argument `-{}`, did you mean `--{}`?", name, arg.long)),
+        None => Err(format!("Unknown argument `-{}`! See `--help`.", name)),
+    }
 }
 
-pub fn get_config () -> SpinnConfig {
-    let mut args: Vec<String> = std::env::args().collect();
-    args.remove(0);
+/// Sets the argument `long` to `value`(e.g. for defaults from the
+/// config file).
+pub fn set (config: &mut SpinnConfig, long: &str, value: &str) -> Result<(), String> {
+    match ARGS.iter().find(|a| a.long == long) {
+        Some(arg) => (arg.set)(config, value),
+        None => Err(format!("unknown argument `{}`", long)),
+    }
+}
 
-    let mut config: SpinnConfig = SpinnConfig {
-        show_help: None,
-        export_as: None,
-        multi_line: None,
-        quote: None,
+/// Reads the arguments, along with errors for the ones that are unknown
+/// or have an invalid value.
+pub fn get_config () -> (SpinnConfig, Vec<String>) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    parse(&args)
+}
 
-        source: None,
-        pick: None,
-        interval: None,
-    };
+pub fn parse (args: &[String]) -> (SpinnConfig, Vec<String>) {
+    let mut config = SpinnConfig::default();
+    let mut errors = Vec::new();
     let mut position = 0;
 
     while position < args.len() {
-        let item = args[position].to_owned();
-
-        if item.starts_with("--") && item.contains('=') {
-            let parts: Vec<&str> = item.trim_start_matches("--").split('=').collect();
-
-            if parts[0] == "interval" {
-                config.interval = match parts[1].parse::<u32>() {
-                    Ok(v) => Some(v),
-                    Err(_) => None
-                };
-            } else if parts[0] == "source" {
-                config.source = Some(parts[1].to_owned());
-            } else if parts[0] == "multiline" {
-                config.multi_line = match parts[1].parse::<bool>() {
-                    Ok(v) => Some(v),
-                    Err(_) => None
-                };
-            } else if parts[0] == "quote" {
-                config.quote = match parts[1].parse::<char>() {
-                    Ok(v) => Some(v),
-                    Err(_) => None
-                };
-            } else if parts[0] == "export" {
-                match parts[1] {
-                    "list" => {
-                        config.export_as = Some(export::SpinnExport::List);
-                    },
-                    "array" => {
-                        config.export_as = Some(export::SpinnExport::Array);
-                    },
-                    "string" => {
-                        config.export_as = Some(export::SpinnExport::String);
-                    },
-                    _ => {}
-                };
-
-                // config.export_as = Some(parts[1].to_owned());
+        let item = &args[position];
+        position += 1;
+
+        // Everything after `--` is a spinner name.
+        if item == "--" {
+            for name in &args[position..] {
+                set_pick(&mut config, &mut errors, name);
             }
-        } else if item.starts_with("-") && item.contains('=') {
-            let parts: Vec<&str> = item.trim_start_matches("-").split('=').collect();
-
-            if parts[0] == "i" {
-                config.interval = match parts[1].parse::<u32>() {
-                    Ok(v) => Some(v),
-                    Err(_) => None
-                };
-            } else if parts[0] == "s" {
-                config.source = Some(parts[1].to_owned());
-            } else if parts[0] == "m" {
-                config.multi_line = match parts[1].parse::<bool>() {
-                    Ok(v) => Some(v),
-                    Err(_) => None
-                };
-            } else if parts[0] == "q" {
-                config.quote = match parts[1].parse::<char>() {
-                    Ok(v) => Some(v),
-                    Err(_) => None
-                };
-            } else if parts[0] == "e" {
-                match parts[1] {
-                    "l" => {
-                        config.export_as = Some(export::SpinnExport::List);
-                    },
-                    "a" => {
-                        config.export_as = Some(export::SpinnExport::Array);
-                    },
-                    "s" => {
-                        config.export_as = Some(export::SpinnExport::String);
-                    },
-                    _ => {}
-                };
+
+            break;
+        }
+
+        if !item.starts_with('-') || item == "-" {
+            // The first word can be a command, `--` is needed for
+            // spinners named like one.
+            let command = COMMANDS.iter().find(|c| c.name == item);
+
+            match command {
+                Some(command) if config.command.is_none() && config.pick.is_none() => {
+                    config.command = Some(command.command);
+                },
+                _ => set_pick(&mut config, &mut errors, item),
             }
-        } else if item == "--help" || item == "-h" {
-            config.show_help = Some(true);
+
+            continue;
+        }
+
+        let (flag, inline) = match item.split_once('=') {
+            Some((flag, value)) => (flag, Some(value)),
+            None => (item.as_str(), None),
+        };
+
+        let found = match flag.strip_prefix("--") {
+            Some(long) => find_long(long),
+            None => find_short(&flag[1..]),
+        };
+        let arg = match found {
+            Ok(arg) => arg,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            },
+        };
+
+        let value = match (arg.takes, inline) {
+            (Takes::Nothing, Some(_)) => {
+                errors.push(format!("`{}` doesn't take a value.", flag));
+                continue;
+            },
+            (Takes::Nothing, None) => "",
+            (_, Some(value)) => value,
+            (Takes::Bool, None) => match args.get(position).map(|v| v.as_str()) {
+                Some(next @ ("true" | "false")) => {
+                    position += 1;
+                    next
+                },
+                _ => "true",
+            },
+            (Takes::Value(placeholder), None) => match args.get(position) {
+                Some(next) => {
+                    position += 1;
+                    next
+                },
+                None => {
+                    errors.push(format!("`{}` needs a value, e.g. `{}={}`.", flag, flag, placeholder));
+                    continue;
+                },
+            },
+        };
+
+        if let Err(error) = (arg.set)(&mut config, value) {
+            errors.push(format!("Invalid value for `{}`: {}.", flag, error));
+        }
+
+        // The rest doesn't matter when showing the help message.
+        if config.show_help.is_some() {
             break;
-        } else {
-            config.pick = Some(item.to_owned());
         }
+    }
 
-        position += 1;
+    (config, errors)
+}
+
+fn set_pick (config: &mut SpinnConfig, errors: &mut Vec<String>, name: &str) {
+    if let Some(command) = COMMANDS.iter().find(|c| Some(c.command) == config.command && c.operand.is_none()) {
+        errors.push(format!("Unexpected argument `{}`, `{}` doesn't take a spinner name.", name, command.name));
+        return;
+    }
+
+    match &config.pick {
+        Some(pick) => errors.push(format!("Unexpected argument `{}`, `{}` was already picked.", name, pick)),
+        None => config.pick = Some(name.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str (args: &[&str]) -> (SpinnConfig, Vec<String>) {
+        parse(&args.iter().map(|v| v.to_string()).collect::<Vec<String>>())
     }
 
-    config
+    #[test]
+    fn values_inline_or_after () {
+        // Only the first `=` splits the value off.
+        let (config, errors) = parse_str(&["--source=a=b.json", "-s", "c.json"]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.sources, ["a=b.json", "c.json"]);
+
+        let (config, errors) = parse_str(&["--interval", "200", "dots"]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.interval, Some(200));
+        assert_eq!(config.pick.as_deref(), Some("dots"));
+
+        let (_, errors) = parse_str(&["--interval"]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("needs a value"), "{:?}", errors);
+    }
+
+    #[test]
+    fn bool_values_are_optional () {
+        let (config, errors) = parse_str(&["--multiline"]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.multi_line, Some(true));
+
+        let (config, errors) = parse_str(&["--multiline", "false", "dots"]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.multi_line, Some(false));
+        assert_eq!(config.pick.as_deref(), Some("dots"));
+
+        // Anything else after it is a spinner name.
+        let (config, _) = parse_str(&["--multiline", "dots"]);
+        assert_eq!(config.multi_line, Some(true));
+        assert_eq!(config.pick.as_deref(), Some("dots"));
+    }
+
+    #[test]
+    fn unknown_arguments_get_a_suggestion () {
+        let (config, errors) = parse_str(&["--interva", "200"]);
+        assert_eq!(config.interval, None);
+        assert!(errors.iter().any(|e| e.contains("did you mean `--interval`?")), "{:?}", errors);
+
+        let (_, errors) = parse_str(&["-theme=dark"]);
+        assert!(errors.iter().any(|e| e.contains("did you mean `--theme`?")), "{:?}", errors);
+    }
+
+    #[test]
+    fn commands_and_names () {
+        let (config, errors) = parse_str(&["list"]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.command, Some(Command::List));
+        assert_eq!(config.pick, None);
+
+        // Spinners named like a command come after `--`.
+        let (config, errors) = parse_str(&["--", "list"]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.command, None);
+        assert_eq!(config.pick.as_deref(), Some("list"));
+
+        let (config, errors) = parse_str(&["show", "--", "list"]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.command, Some(Command::Show));
+        assert_eq!(config.pick.as_deref(), Some("list"));
+    }
 }
@@ -0,0 +1,331 @@
+// Commands that print something instead of showing the preview
+use regex::Regex;
+use serde_json::json;
+use std::{collections::HashMap, io::{self, stdout, Write}, path::Path};
+use unicode_width::UnicodeWidthChar;
+
+use crate::{args::Format, json, layout, term, width, Spinn};
+
+/// Which spinners `list` prints.
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// Regex the name should match.
+    pub pattern: Option<String>,
+    pub tags: Vec<String>,
+    pub contains: Option<String>,
+    pub max_width: Option<usize>,
+}
+
+impl Spinn {
+    /// Widest frame of a spinner.
+    fn max_width (&self, name: &str) -> usize {
+        self.frames(name).iter().map(|f| width::width(f)).max().unwrap_or(0)
+    }
+
+    /// Prints the spinners that pass `filter`.
+    /// An invalid pattern is an `InvalidInput` error.
+    pub fn list (&self, filter: &Filter, format: Format) -> io::Result<()> {
+        let t = &self.theme;
+        let mut out = stdout().lock();
+        let pattern = match &filter.pattern {
+            Some(p) => Some(Regex::new(p).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid pattern `{}`: {}", p, e)))?),
+            None => None,
+        };
+
+        let names: Vec<String> = self.keys.iter().filter(|&name| {
+            let frames = self.frames(name);
+            let tags = self.meta.get(name).map(|m| m.tags.as_slice()).unwrap_or_default();
+
+            pattern.as_ref().is_none_or(|p| p.is_match(name))
+                && filter.tags.iter().all(|tag| tags.contains(tag))
+                && filter.contains.as_ref().is_none_or(|text| frames.iter().any(|f| f.contains(text.as_str())))
+                && filter.max_width.is_none_or(|max| self.max_width(name) <= max)
+        }).cloned().collect();
+
+        let rows: Vec<[String; 7]> = names.iter().map(|name| {
+            let meta = self.meta.get(name).cloned().unwrap_or_default();
+
+            [
+                name.to_owned(),
+                self.frames(name).len().to_string(),
+                self.max_width(name).to_string(),
+                meta.interval.map(|i| i.to_string()).unwrap_or_default(),
+                meta.tags.join(","),
+                meta.pack,
+                meta.source,
+            ]
+        }).collect();
+
+        match format {
+            Format::Json => {
+                let output: Vec<_> = names.iter().map(|name| {
+                    let meta = self.meta.get(name).cloned().unwrap_or_default();
+
+                    json!({
+                        "name": name,
+                        "frames": self.frames(name).len(),
+                        "width": self.max_width(name),
+                        "interval": meta.interval,
+                        "tags": meta.tags,
+                        "aliases": meta.aliases,
+                        "pack": meta.pack,
+                        "source": meta.source,
+                    })
+                }).collect();
+
+                writeln!(out, "{}", serde_json::to_string_pretty(&output).unwrap_or_default())?;
+            },
+            Format::Tsv => {
+                writeln!(out, "name\tframes\twidth\tinterval\ttags\tpack\tsource")?;
+
+                for row in rows {
+                    writeln!(out, "{}", row.join("\t"))?;
+                }
+            },
+            Format::Plain => {
+                // Columns are as wide as their widest value.
+                let headers = [ "Name", "Frames", "Width", "Interval", "Tags", "Pack", "Source" ];
+                let sizes: Vec<usize> = (0..headers.len()).map(|c| {
+                    rows.iter().map(|r| width::width(&r[c])).chain([ headers[c].len() ]).max().unwrap_or(0)
+                }).collect();
+
+                let header: Vec<String> = headers.iter().zip(&sizes).map(|(h, size)| width::pad(h, *size)).collect();
+                writeln!(out, "{}", t.accent.underline().paint(header.join("  ").trim_end()))?;
+
+                for row in rows {
+                    let cells: Vec<String> = row.iter().zip(&sizes).map(|(c, size)| width::pad(c, *size)).collect();
+
+                    writeln!(
+                        out,
+                        "{}  {}",
+                        t.key.paint(&cells[0]),
+                        t.text.paint(cells[1..].join("  ").trim_end()),
+                    )?;
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Prints every frame of the picked spinner side by side, wrapped to
+    /// the width of the terminal.
+    pub fn strip (&self) -> io::Result<()> {
+        let t = &self.theme;
+        let mut out = stdout().lock();
+        let frames = self.frames(&self.pick);
+        let terminal_w = layout::terminal_width() as usize;
+
+        let mut line: Vec<String> = Vec::new();
+        let mut used = 0;
+
+        for frame in frames.iter() {
+            let frame = term::adapt(frame);
+            let size = width::width(&frame);
+
+            if !line.is_empty() && used + 2 + size > terminal_w {
+                writeln!(out, "{}", t.spinner.paint(line.join("  ")))?;
+
+                line.clear();
+                used = 0;
+            }
+
+            used += if line.is_empty() { size } else { 2 + size };
+            line.push(frame);
+        }
+
+        if !line.is_empty() {
+            writeln!(out, "{}", t.spinner.paint(line.join("  ")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints the details of the picked spinner.
+    pub fn info (&self) -> io::Result<()> {
+        let t = &self.theme;
+        let mut out = stdout().lock();
+        let frames = self.frames(&self.pick);
+        let meta = self.meta.get(&self.pick).cloned().unwrap_or_default();
+        let widths: Vec<usize> = frames.iter().map(|f| width::width(f)).collect();
+
+        let min_w = widths.iter().min().copied().unwrap_or(0);
+        let max_w = widths.iter().max().copied().unwrap_or(0);
+
+        let details = [
+            ("Name", self.pick.to_owned()),
+            ("Frames", frames.len().to_string()),
+            ("Width", if min_w == max_w { max_w.to_string() } else { format!("{}-{}", min_w, max_w) }),
+            ("Interval", format!("{}ms", self.interval)),
+            ("Duration", format!("{}ms", frames.len() as u64 * self.interval as u64)),
+            ("Favorite", if self.state.is_favorite(&self.pick) { "yes" } else { "no" }.to_owned()),
+            ("Tags", meta.tags.join(", ")),
+            ("Aliases", meta.aliases.join(", ")),
+            ("Pack", meta.pack),
+            ("Source", meta.source),
+        ];
+
+        for (key, value) in details {
+            writeln!(out, "{}  {}", t.key.paint(width::pad(key, 10)), t.value.paint(value))?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints the problems found in the spinners, padding the frames with
+    /// different widths when `fix` is set. Only the spinners read from
+    /// `sources` are checked, unless it's empty. Returns `false` if there
+    /// were any problems(that weren't fixed).
+    pub fn validate (&self, fix: bool, sources: &[String], invalid: &[json::Invalid]) -> io::Result<bool> {
+        let t = &self.theme;
+        let mut out = stdout().lock();
+        let mut problems = 0;
+        let mut warnings = 0;
+        let mut fixed = 0;
+        // First spinner with each list of frames.
+        let mut seen: HashMap<&[String], &str> = HashMap::new();
+
+        let checked = |source: &str| sources.is_empty() || sources.iter().any(|s| Path::new(source).starts_with(s));
+
+        for entry in invalid.iter().filter(|i| checked(&i.source)) {
+            writeln!(out, "{}: {}", t.key.paint(&entry.name), t.text.paint(format!("{} ({})", entry.problem, entry.source)))?;
+            problems += 1;
+        }
+
+        for name in &self.keys {
+            let frames = &self.items[name];
+            let meta = self.meta.get(name).cloned().unwrap_or_default();
+            let mut report = |style: term::Style, text: String| {
+                writeln!(out, "{}: {}", t.key.paint(name), style.paint(text))
+            };
+
+            // Spinners from other files still count as the first ones with
+            // their frames.
+            if !checked(&meta.source) {
+                if !frames.is_empty() {
+                    seen.entry(frames).or_insert(name);
+                }
+
+                continue;
+            }
+
+            for problem in lint(frames) {
+                report(t.text, problem)?;
+                problems += 1;
+            }
+
+            // Some terminals draw these in 2 cells.
+            let ambiguous = ambiguous_width(frames);
+
+            if !ambiguous.is_empty() {
+                report(t.warning, format!("has characters with an ambiguous width: {}.", ambiguous.join(" ")))?;
+                warnings += 1;
+            }
+
+            match seen.get(frames.as_slice()) {
+                Some(first) if !frames.is_empty() => {
+                    report(t.text, format!("has the same frames as `{}`, use `aliases` instead.", first))?;
+                    problems += 1;
+                },
+                _ => {
+                    seen.insert(frames, name);
+                },
+            }
+
+            let widths: Vec<usize> = frames.iter().map(|f| width::width(f)).collect();
+            let max_w = widths.iter().max().copied().unwrap_or(0);
+
+            if widths.iter().all(|w| *w == max_w) {
+                continue;
+            }
+
+            report(t.text, format!("frames are {} to {} cells wide.", widths.iter().min().copied().unwrap_or(0), max_w))?;
+
+            // The built-in spinners are left alone.
+            if !fix || meta.pack == "builtin" {
+                problems += 1;
+                continue;
+            }
+
+            let padded = width::normalize(frames, self.align, self.fill);
+
+            match json::save_spinner(&meta.source, &meta.name, &padded) {
+                Ok(_) => {
+                    report(t.accent, format!("padded to {} cells in {}.", max_w, meta.source))?;
+                    fixed += 1;
+                },
+                Err(e) => {
+                    report(t.error, format!("failed to save {}: {}", meta.source, e))?;
+                    problems += 1;
+                },
+            }
+        }
+
+        if fixed > 0 {
+            writeln!(out, "{}", t.accent.paint(format!("Fixed {} problem(s).", fixed)))?;
+        }
+
+        if warnings > 0 {
+            writeln!(out, "{}", t.warning.paint(format!("Found {} warning(s).", warnings)))?;
+        }
+
+        if problems > 0 {
+            writeln!(out, "{}", t.error.paint(format!("Found {} problem(s).", problems)))?;
+        }
+
+        Ok(problems == 0)
+    }
+}
+
+/// Problems of a spinner that only depend on it's own frames.
+fn lint (frames: &[String]) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if frames.is_empty() {
+        problems.push("has no frames.".to_owned());
+        return problems;
+    }
+
+    for (i, frame) in frames.iter().enumerate() {
+        if frame.is_empty() {
+            problems.push(format!("frame {} is empty.", i + 1));
+        }
+
+        if i > 0 && frame == &frames[i - 1] {
+            problems.push(format!("frame {} is the same as frame {}.", i + 1, i));
+        }
+
+        // Escape sequences(e.g. colors) are fine, anything else isn't.
+        let control = width::segments(frame).into_iter().find_map(|segment| match segment {
+            width::Segment::Grapheme(grapheme) => grapheme.chars().find(|c| c.is_control()),
+            width::Segment::Escape(_) => None,
+        });
+
+        if let Some(c) = control {
+            problems.push(format!("frame {} has a control character(U+{:04X}).", i + 1, c as u32));
+        }
+    }
+
+    // The animation would pause when it loops.
+    if frames.len() > 2 && frames.first() == frames.last() {
+        problems.push("the last frame is the same as the first one, the animation stalls when it loops.".to_owned());
+    }
+
+    problems
+}
+
+/// Characters of the frames that are 1 cell wide in some terminals & 2 in
+/// others(e.g. CJK ones).
+fn ambiguous_width (frames: &[String]) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+
+    for c in frames.iter().flat_map(|f| f.chars()) {
+        let ambiguous = UnicodeWidthChar::width(c) != UnicodeWidthChar::width_cjk(c);
+
+        if ambiguous && !found.contains(&c.to_string()) {
+            found.push(c.to_string());
+        }
+    }
+
+    found
+}
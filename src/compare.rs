@@ -0,0 +1,444 @@
+// Side-by-side comparison of spinners
+use crossterm::{
+    cursor::{self, MoveUp},
+    event::{self, Event, MouseButton, MouseEvent, MouseEventKind},
+    execute,
+    terminal::{Clear, ClearType}
+};
+use std::{io::stdout, time::Duration};
+
+use crate::{json::SpinnJSON, keymap::{Action, Keymap, Scope}, layout, render::{Renderer, Terminal}, term, theme::Theme, ticker::Ticker, width};
+
+/// Number of lines drawn by the comparison view.
+const HEIGHT: u16 = 6;
+
+struct Panel {
+    name: String,
+    frame: usize,
+    /// Interval used when the intervals aren't shared.
+    interval: u32,
+    ticker: Ticker,
+}
+
+pub struct Compare<'a> {
+    items: &'a SpinnJSON,
+    /// Spinner names in the current sort order.
+    keys: Vec<String>,
+
+    panels: Vec<Panel>,
+    focus: usize,
+
+    /// Whether every panel uses `interval`.
+    shared: bool,
+    interval: u32,
+    /// Whether every panel is restarted at the end of the longest loop.
+    synced: bool,
+    cycle: Ticker,
+
+    /// Row where the view starts, used for mouse events.
+    top: u16,
+    terminal: Terminal,
+
+    keymap: &'a Keymap,
+    theme: &'a Theme,
+}
+
+impl<'a> Compare<'a> {
+    pub fn new (items: &'a SpinnJSON, keys: Vec<String>, names: &[String], interval: u32, keymap: &'a Keymap, theme: &'a Theme) -> Compare<'a> {
+        Compare {
+            items,
+            keys,
+
+            panels: names.iter().map(|name| Panel {
+                name: name.to_owned(),
+                frame: 0,
+                interval,
+                ticker: Ticker::new(interval),
+            }).collect(),
+            focus: 0,
+
+            shared: true,
+            interval,
+            synced: false,
+            cycle: Ticker::new(interval),
+
+            top: 0,
+            terminal: Terminal::default(),
+
+            keymap,
+            theme,
+        }
+    }
+
+    /// Names of the spinners being compared.
+    pub fn names (&self) -> Vec<String> {
+        self.panels.iter().map(|p| p.name.to_owned()).collect()
+    }
+
+    pub fn interval (&self) -> u32 {
+        self.interval
+    }
+
+    fn panel_interval (&self, panel: &Panel) -> u32 {
+        if self.shared { self.interval } else { panel.interval }
+    }
+
+    //|fS "chunk: Panels"
+
+    /// Restarts every panel on the same tick.
+    fn sync (&mut self) {
+        for p in 0..self.panels.len() {
+            let interval = self.panel_interval(&self.panels[p]);
+
+            self.panels[p].frame = 0;
+            self.panels[p].ticker.reset(interval);
+        }
+
+        self.cycle.reset(self.cycle_length());
+    }
+
+    /// Length of the longest loop in milliseconds.
+    fn cycle_length (&self) -> u32 {
+        self.panels.iter().map(|panel| {
+            let frames = self.items[&panel.name].len().max(1) as u32;
+            frames.saturating_mul(self.panel_interval(panel))
+        }).max().unwrap_or(self.interval)
+    }
+
+    /// Panels changed, synced ones start over together.
+    fn changed (&mut self) {
+        if self.synced {
+            self.sync();
+        }
+    }
+
+    /// Shows the spinner `offset` names away in the focused panel.
+    fn step_spinner (&mut self, offset: isize) {
+        let panel = &mut self.panels[self.focus];
+        let current = self.keys.iter().position(|k| k == &panel.name).unwrap_or(0) as isize;
+        let len = self.keys.len() as isize;
+
+        panel.name = self.keys[(current + offset).rem_euclid(len) as usize].to_owned();
+        panel.frame = 0;
+        self.changed();
+    }
+
+    fn add_panel (&mut self) {
+        let name = self.panels[self.focus].name.to_owned();
+        let interval = self.panels[self.focus].interval;
+
+        self.panels.insert(self.focus + 1, Panel { name, frame: 0, interval, ticker: Ticker::new(interval) });
+        self.focus += 1;
+        self.step_spinner(1);
+    }
+
+    fn remove_panel (&mut self) {
+        if self.panels.len() < 2 {
+            return;
+        }
+
+        self.panels.remove(self.focus);
+        self.focus = self.focus.min(self.panels.len() - 1);
+        self.changed();
+    }
+
+    fn change_interval (&mut self, by: i32) {
+        let current = if self.shared { self.interval } else { self.panels[self.focus].interval };
+
+        if (by < 0 && current <= 100) || (by > 0 && current >= 1000) {
+            return;
+        }
+
+        let updated = current.saturating_add_signed(by);
+
+        if self.shared {
+            self.interval = updated;
+        } else {
+            self.panels[self.focus].interval = updated;
+        }
+
+        self.changed();
+    }
+
+    /// Advances the panels whose frame is due. Returns how long to wait
+    /// for the next one.
+    fn tick (&mut self) -> Duration {
+        let mut wait = Duration::from_millis(1000);
+
+        if self.synced {
+            if self.cycle.due(self.cycle_length()) {
+                self.sync();
+            }
+
+            wait = wait.min(self.cycle.remaining());
+        }
+
+        for p in 0..self.panels.len() {
+            let interval = self.panel_interval(&self.panels[p]);
+            let max = self.items[&self.panels[p].name].len();
+            let panel = &mut self.panels[p];
+
+            if panel.ticker.due(interval) {
+                panel.frame = (panel.frame + 1) % max.max(1);
+            }
+
+            wait = wait.min(panel.ticker.remaining());
+        }
+
+        wait
+    }
+
+    //|fE
+
+    //|fS "chunk: Input"
+
+    /// Handles a key. Returns `true` when the view should be closed.
+    fn key (&mut self, action: Option<Action>) -> bool {
+        match action {
+            Some(Action::Quit) => return true,
+            Some(Action::Slower) => self.change_interval(50),
+            Some(Action::Faster) => self.change_interval(-50),
+            Some(Action::Next) => self.step_spinner(1),
+            Some(Action::Previous) => self.step_spinner(-1),
+            Some(Action::FocusNext) => {
+                self.focus = (self.focus + 1) % self.panels.len();
+            },
+            Some(Action::FocusPrevious) => {
+                self.focus = (self.focus + self.panels.len() - 1) % self.panels.len();
+            },
+            Some(Action::AddPanel) => self.add_panel(),
+            Some(Action::RemovePanel) => self.remove_panel(),
+            Some(Action::Sync) => {
+                self.synced = !self.synced;
+                self.changed();
+            },
+            Some(Action::ToggleShared) => {
+                self.shared = !self.shared;
+
+                // Panels start with the interval that was shared.
+                if !self.shared {
+                    for panel in self.panels.iter_mut() {
+                        panel.interval = self.interval;
+                    }
+                }
+
+                self.changed();
+            },
+            _ => {}
+        }
+
+        false
+    }
+
+    /// Handles mouse events.
+    fn mouse (&mut self, mouse: MouseEvent) {
+        let panel_w = (layout::terminal_width() as usize / self.panels.len()).max(1);
+        let panel = (mouse.column as usize / panel_w).min(self.panels.len() - 1);
+
+        match mouse.kind {
+            // Scrubbing through the frames of a panel with it's stats.
+            MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left) if mouse.row == self.top + 4 => {
+                let max = self.items[&self.panels[panel].name].len();
+                let x = mouse.column as usize - panel * panel_w;
+
+                self.focus = panel;
+                self.panels[panel].frame = (x * max / panel_w).min(max.saturating_sub(1));
+            },
+            MouseEventKind::Down(MouseButton::Left) if (self.top..self.top + HEIGHT - 1).contains(&mouse.row) => {
+                self.focus = panel;
+            },
+            MouseEventKind::ScrollUp => self.change_interval(50),
+            MouseEventKind::ScrollDown => self.change_interval(-50),
+            _ => {}
+        }
+    }
+
+    //|fE
+
+    //|fS "chunk: Drawing"
+
+    fn clear_output (&self) {
+        execute!(
+            stdout(),
+
+            MoveUp(HEIGHT),
+            Clear(ClearType::FromCursorDown),
+        ).ok();
+    }
+
+    fn draw (&self, r: &mut dyn Renderer) {
+        let t = self.theme;
+        let terminal_w = r.size().0;
+        let panel_w = terminal_w / self.panels.len();
+
+        // Names
+        for (p, panel) in self.panels.iter().enumerate() {
+            let focused = p == self.focus;
+
+            let x = r.print(p * panel_w + 1, 0, if focused { "󰁔" } else { " " }, &t.accent);
+            r.print(x + 1, 0, &layout::truncate(&panel.name, panel_w.saturating_sub(4)), &if focused { t.title.bold() } else { t.text });
+        }
+
+        // Spinners
+        for (p, panel) in self.panels.iter().enumerate() {
+            let frames = &self.items[&panel.name];
+            let frame = layout::truncate(&term::adapt(frames.get(panel.frame).map(|f| f.as_str()).unwrap_or_default()), panel_w);
+
+            r.print(p * panel_w, 2, &width::center(&frame, panel_w), &t.spinner);
+        }
+
+        let mode = format!("{}{}", if self.shared { "󰓦 Shared interval" } else { "󰓧 Independent intervals" }, if self.synced { " • 󰑓 Synced" } else { "" });
+        let mode = layout::truncate(&mode, terminal_w);
+        r.print(width::center_pad(&mode, terminal_w), 3, &mode, &t.muted);
+
+        // Stats
+        for (p, panel) in self.panels.iter().enumerate() {
+            let max = self.items[&panel.name].len();
+            let frame_size = max.to_string().chars().count();
+            let stat = format!("󰄉 {}ms • 󰕟 {:0frame_size$}/{}", self.panel_interval(panel), panel.frame + 1, max);
+
+            r.print(p * panel_w, 4, &width::center(&layout::truncate(&stat, panel_w), panel_w), if p == self.focus { &t.value } else { &t.muted });
+        }
+
+        // Keymaps
+        let keymap = self.keymap.footer(Scope::Compare);
+        let keymap_text: Vec<String> = keymap.iter().map(|(key, label)| format!("{}: {}", key, label)).collect();
+
+        let icon_size = width::width("󰌏  ");
+        let keymap_count = layout::fitting(&keymap_text, ", ", terminal_w.saturating_sub(icon_size + 2));
+        let keymap_len = icon_size + width::width(&keymap_text[..keymap_count].join(", ")) + if keymap_count < keymap.len() { 2 } else { 0 };
+
+        if keymap_count > 0 {
+            let mut x = r.print(layout::center_pad(keymap_len, terminal_w), 5, "󰌏  ", &t.title);
+
+            for (k, (key, label)) in keymap.iter().take(keymap_count).enumerate() {
+                x = r.print(x, 5, key, &t.value.bold());
+                x = r.print(x, 5, ": ", &t.text);
+                x = r.print(x, 5, label, &t.accent);
+                x = r.print(x, 5, if k + 1 < keymap_count { ", " } else { "" }, &t.text);
+            }
+
+            if keymap_count < keymap.len() {
+                r.print(x, 5, " …", &t.muted);
+            }
+        }
+    }
+
+    fn render (&mut self) {
+        let mut terminal = std::mem::take(&mut self.terminal);
+
+        terminal.begin(layout::terminal_width() as usize, HEIGHT as usize, self.top);
+        self.draw(&mut terminal);
+        terminal.flush();
+
+        self.terminal = terminal;
+    }
+
+    //|fE
+
+    /// Runs the comparison until it is closed.
+    pub fn run (&mut self) {
+        for _ in 0..HEIGHT {
+            println!();
+        }
+
+        self.clear_output();
+        self.top = cursor::position().map(|p| p.1).unwrap_or(0);
+        self.sync();
+        self.render();
+
+        let mut wait = self.tick();
+
+        loop {
+            //|fS "chunk: Comparison loop"
+
+            if event::poll(wait).unwrap() {
+                match event::read().unwrap() {
+                    Event::Key(key) if self.key(self.keymap.action(Scope::Compare, &key)) => {
+                        self.clear_output();
+                        break;
+                    },
+                    Event::Resize(_, _) => {
+                        self.clear_output();
+                        self.top = cursor::position().map(|p| p.1).unwrap_or(0);
+                        self.terminal.invalidate();
+                    },
+                    Event::Mouse(mouse) => {
+                        self.mouse(mouse);
+                    },
+                    _ => {}
+                }
+            }
+
+            // Frames are only advanced when they are due, so input
+            // doesn't change the timing. Only the cells that changed
+            // are drawn again.
+            wait = self.tick();
+            self.render();
+
+            //|fE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Buffer;
+
+    #[test]
+    fn draw_fits_the_width () {
+        let items: SpinnJSON = [
+            ("clock".to_owned(), vec![ "時計".to_owned(), "計時".to_owned() ]),
+            ("empty".to_owned(), Vec::new()),
+        ].into_iter().collect();
+        let names = vec![ "clock".to_owned(), "empty".to_owned() ];
+
+        let keymap = Keymap::from_config(None).0;
+        let theme = Theme::from_config("dark", None).0;
+        let cmp = Compare::new(&items, names.clone(), &names, 100, &keymap, &theme);
+
+        for w in [ 20, 40, 80 ] {
+            let mut buffer = Buffer::new(w, HEIGHT as usize);
+            cmp.draw(&mut buffer);
+
+            let lines: Vec<String> = buffer.to_string().lines().map(|l| l.to_owned()).collect();
+            assert!(lines.iter().all(|l| width::width(l) == w), "{:?}", lines);
+            assert_eq!(lines[2].trim(), "時計", "{:?}", lines);
+        }
+    }
+
+    #[test]
+    fn sync_toggle () {
+        let items: SpinnJSON = [
+            ("dots".to_owned(), vec![ "⠋".to_owned(), "⠙".to_owned(), "⠹".to_owned() ]),
+            ("line".to_owned(), vec![ "-".to_owned(), "|".to_owned() ]),
+        ].into_iter().collect();
+        let names = vec![ "dots".to_owned(), "line".to_owned() ];
+
+        let keymap = Keymap::from_config(None).0;
+        let theme = Theme::from_config("dark", None).0;
+        let mut cmp = Compare::new(&items, names.clone(), &names, 100, &keymap, &theme);
+
+        cmp.panels[0].frame = 2;
+        cmp.key(Some(Action::Sync));
+
+        // Turning it on restarts the panels, the longest loop sets the cycle.
+        assert!(cmp.synced);
+        assert!(cmp.panels.iter().all(|p| p.frame == 0));
+        assert_eq!(cmp.cycle_length(), 300);
+
+        cmp.key(Some(Action::ToggleShared));
+        cmp.focus = 1;
+        cmp.key(Some(Action::Slower));
+        cmp.key(Some(Action::Slower));
+        assert_eq!(cmp.cycle_length(), 400);
+
+        let mut buffer = Buffer::new(80, HEIGHT as usize);
+        cmp.draw(&mut buffer);
+        assert!(buffer.to_string().lines().nth(3).unwrap().contains("Synced"));
+
+        cmp.key(Some(Action::Sync));
+        assert!(!cmp.synced);
+    }
+}
@@ -0,0 +1,304 @@
+// Completion scripts for bash, zsh & fish
+use crate::args::{self, Arg, Complete, Takes, ARGS, COMMANDS, SHELLS};
+
+/// Help text without the markup used by the help message.
+fn plain (text: &str) -> String {
+    text.chars().filter(|c| !"`[]{}".contains(*c)).collect::<String>().trim_end_matches('.').to_owned()
+}
+
+/// Commands shown to the user.
+fn commands () -> impl Iterator<Item = &'static args::Subcommand> {
+    COMMANDS.iter().filter(|c| !c.name.starts_with("__"))
+}
+
+/// Escapes the characters a shell would treat specially(e.g. `"`).
+fn escape_word (word: &str) -> String {
+    word.chars().map(|c| if c.is_alphanumeric() || "-_".contains(c) { c.to_string() } else { format!("\\{}", c) }).collect()
+}
+
+/// `--name` & `-n` of an argument.
+fn names (arg: &Arg) -> Vec<String> {
+    let mut names = vec![ format!("--{}", arg.long) ];
+    names.extend(arg.short.map(|c| format!("-{}", c)));
+    names
+}
+
+pub fn script (shell: &str) -> Result<String, String> {
+    match shell {
+        "bash" => Ok(bash()),
+        "zsh" => Ok(zsh()),
+        "fish" => Ok(fish()),
+        "" => Err(format!("`completions` needs a shell, one of {}.", SHELLS.join(", "))),
+        _ => Err(format!("Unknown shell `{}`! Expected one of {}.", shell, SHELLS.join(", "))),
+    }
+}
+
+//|fS "chunk: bash"
+
+fn bash () -> String {
+    let mut values = String::new();
+
+    for arg in ARGS {
+        let reply = match arg.complete {
+            Complete::Nothing => continue,
+            Complete::Choices(choices) => {
+                let words: Vec<String> = choices.iter().map(|c| format!("'{}'", c.replace('\'', "'\\''"))).collect();
+                format!("_spinn_rs_choices \"$value\" {}", words.join(" "))
+            },
+            Complete::Path => "COMPREPLY=($(compgen -f -- \"$value\"))".to_owned(),
+            Complete::Spinners => "_spinn_rs_spinners \"$value\"".to_owned(),
+        };
+
+        values += &format!("        {})\n            {}\n            return ;;\n", names(arg).join("|"), reply);
+    }
+
+    let flags: Vec<String> = ARGS.iter().flat_map(names).collect();
+    let commands: Vec<&str> = commands().map(|c| c.name).collect();
+
+    format!(r#"# bash completion for spinn-rs
+
+# Choices starting with `$1`, escaped for the command line.
+_spinn_rs_choices() {{
+    local choice
+    COMPREPLY=()
+    for choice in "${{@:2}}"; do
+        [[ "$choice" == "$1"* ]] && COMPREPLY+=("$(printf '%q' "$choice")")
+    done
+}}
+
+# Spinner names, read from the same sources as the command line.
+_spinn_rs_spinners() {{
+    local sources=() i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${{COMP_WORDS[i]}}" in
+            --source|-s)
+                [[ "${{COMP_WORDS[i+1]}}" == "=" ]] && sources+=("--source=${{COMP_WORDS[i+2]}}") || sources+=("--source=${{COMP_WORDS[i+1]}}") ;;
+        esac
+    done
+
+    # `--compare` takes comma separated names.
+    local head=""
+    [[ "$1" == *,* ]] && head="${{1%,*}},"
+    COMPREPLY=($(compgen -P "$head" -W "$(spinn-rs __spinners "${{sources[@]}}" 2>/dev/null)" -- "${{1##*,}}"))
+}}
+
+_spinn_rs() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local option="${{COMP_WORDS[COMP_CWORD-1]}}" value="$cur"
+
+    # `=` is a separate word for bash.
+    if [[ "$cur" == "=" ]]; then
+        value=""
+    elif [[ "$option" == "=" ]]; then
+        option="${{COMP_WORDS[COMP_CWORD-2]}}"
+    fi
+
+    case "$option" in
+{values}    esac
+
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W '{flags}' -- "$cur"))
+        return
+    fi
+
+    # The first word can be a command.
+    local i command=""
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${{COMP_WORDS[i]}}" in
+            -*|=) ;;
+            *)
+                [[ "${{COMP_WORDS[i-1]}}" == "=" ]] && continue
+                command="${{COMP_WORDS[i]}}"
+                break ;;
+        esac
+    done
+
+    case "$command" in
+        "")
+            _spinn_rs_spinners "$cur"
+            COMPREPLY+=($(compgen -W '{commands}' -- "$cur")) ;;
+        completions)
+            COMPREPLY=($(compgen -W '{shells}' -- "$cur")) ;;
+        config)
+            COMPREPLY=($(compgen -W 'path' -- "$cur")) ;;
+        list|validate|schema) ;;
+        *)
+            _spinn_rs_spinners "$cur" ;;
+    esac
+}}
+
+complete -F _spinn_rs spinn-rs
+"#,
+        values = values,
+        flags = flags.join(" "),
+        commands = commands.join(" "),
+        shells = SHELLS.join(" "),
+    )
+}
+
+//|fE
+
+//|fS "chunk: zsh"
+
+/// Escapes text used inside a single quoted `_arguments` spec.
+fn zsh_escape (text: &str) -> String {
+    text.replace('\'', "'\\''").replace('[', "\\[").replace(']', "\\]").replace(':', "\\:")
+}
+
+fn zsh () -> String {
+    let mut specs = String::new();
+
+    for arg in ARGS {
+        let action = match arg.complete {
+            Complete::Nothing if arg.takes == Takes::Nothing => String::new(),
+            Complete::Nothing => ": ".to_owned(),
+            Complete::Choices(choices) => {
+                let words: Vec<String> = choices.iter().map(|c| escape_word(c)).collect();
+                format!(":{}:({})", arg.long, words.join(" "))
+            },
+            Complete::Path => ":path:_files".to_owned(),
+            Complete::Spinners => ":spinners:_spinn_rs_compare".to_owned(),
+        };
+
+        // `--name=` takes the value after `=` or as the next word, `::`
+        // makes it optional.
+        let suffix = if arg.takes == Takes::Nothing { "" } else { "=" };
+        let action = if arg.takes == Takes::Bool { ":".to_owned() + &action } else { action };
+
+        for name in names(arg) {
+            specs += &format!("        '{}{}[{}]{}' \\\n", name, suffix, zsh_escape(&plain(arg.help)), action.replace('\'', "'\\''"));
+        }
+    }
+
+    let commands: Vec<String> = commands().map(|c| format!("'{}:{}'", c.name, zsh_escape(&plain(c.help)))).collect();
+
+    format!(r#"#compdef spinn-rs
+
+# Spinner names, read from the same sources as the command line.
+_spinn_rs_names() {{
+    local -a sources
+    local i
+    for ((i = 2; i < CURRENT; i++)); do
+        case "${{words[i]}}" in
+            --source=*|-s=*) sources+=("${{words[i]}}") ;;
+            --source|-s) sources+=("--source=${{words[i+1]}}") ;;
+        esac
+    done
+
+    spinn-rs __spinners $sources 2>/dev/null
+}}
+
+_spinn_rs_spinners() {{
+    local -a names
+    names=(${{(f)"$(_spinn_rs_names)"}})
+    compadd -a names
+}}
+
+_spinn_rs_compare() {{
+    local -a names
+    names=(${{(f)"$(_spinn_rs_names)"}})
+    _values -s , spinner $names
+}}
+
+_spinn_rs_first() {{
+    local -a commands
+    commands=({commands})
+    _describe -t commands command commands
+    _spinn_rs_spinners
+}}
+
+_spinn_rs_rest() {{
+    case "${{line[1]}}" in
+        completions) compadd {shells} ;;
+        config) compadd path ;;
+        list|validate|schema) ;;
+        *) _spinn_rs_spinners ;;
+    esac
+}}
+
+_spinn_rs() {{
+    _arguments -s \
+{specs}        '1: :_spinn_rs_first' \
+        '*: :_spinn_rs_rest'
+}}
+
+_spinn_rs "$@"
+"#,
+        specs = specs,
+        commands = commands.join(" "),
+        shells = SHELLS.join(" "),
+    )
+}
+
+//|fE
+
+//|fS "chunk: fish"
+
+/// Escapes text used inside a single quoted fish string.
+fn fish_escape (text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn fish () -> String {
+    let mut lines = String::new();
+
+    for command in commands() {
+        lines += &format!(
+            "complete -c spinn-rs -n __fish_use_subcommand -a {} -d '{}'\n",
+            command.name,
+            fish_escape(&plain(command.help)),
+        );
+    }
+
+    lines += &format!("complete -c spinn-rs -n '__fish_seen_subcommand_from completions' -a '{}'\n", SHELLS.join(" "));
+    lines += "complete -c spinn-rs -n '__fish_seen_subcommand_from config' -a path\n";
+    lines += "complete -c spinn-rs -n 'not __fish_seen_subcommand_from completions config list validate schema' -a '(__spinn_rs_spinners)'\n";
+
+    for arg in ARGS {
+        let mut line = format!("complete -c spinn-rs -l {}", arg.long);
+
+        if let Some(c) = arg.short {
+            line += &format!(" -s {}", c);
+        }
+
+        line += &match arg.complete {
+            Complete::Nothing if arg.takes == Takes::Nothing => String::new(),
+            Complete::Nothing => " -x".to_owned(),
+            Complete::Choices(choices) => {
+                let words: Vec<String> = choices.iter().map(|c| escape_word(c)).collect();
+                format!(" -x -a '{}'", fish_escape(&words.join(" ")))
+            },
+            Complete::Path => " -r -F".to_owned(),
+            Complete::Spinners => " -x -a '(__spinn_rs_spinners)'".to_owned(),
+        };
+
+        lines += &format!("{} -d '{}'\n", line, fish_escape(&plain(arg.help)));
+    }
+
+    format!(r#"# fish completion for spinn-rs
+
+# Spinner names, read from the same sources as the command line.
+function __spinn_rs_spinners
+    set -l tokens (commandline -opc)
+    set -l sources
+    for i in (seq 2 (count $tokens))
+        switch $tokens[$i]
+            case '--source=*' '-s=*'
+                set -a sources $tokens[$i]
+            case --source -s
+                if test $i -lt (count $tokens)
+                    set -a sources --source=$tokens[(math $i + 1)]
+                end
+        end
+    end
+
+    spinn-rs __spinners $sources 2>/dev/null
+end
+
+complete -c spinn-rs -f
+{lines}"#,
+        lines = lines,
+    )
+}
+
+//|fE
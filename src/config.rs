@@ -0,0 +1,106 @@
+// User configuration
+use serde_json::Value;
+use std::{env, fs, path::PathBuf};
+
+use crate::args::{self, SpinnConfig};
+
+/// Config keys that give the default value of an argument with the same
+/// name.
+const DEFAULTS: &[&str] = &[ "interval", "quote", "export", "multiline", "theme", "color", "conflicts", "sort", "pad", "fill" ];
+
+/// Directory containing the config file.
+///
+/// Uses `$XDG_CONFIG_HOME/spinn-rs`, `~/.config/spinn-rs` or `%APPDATA%\spinn-rs`.
+pub fn config_dir () -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(v) if !v.is_empty() => PathBuf::from(v),
+        _ => match env::var_os("HOME") {
+            Some(v) if !v.is_empty() => PathBuf::from(v).join(".config"),
+            _ => PathBuf::from(env::var_os("APPDATA")?),
+        },
+    };
+
+    Some(base.join("spinn-rs"))
+}
+
+pub fn config_path () -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.json"))
+}
+
+/// Reads the config file. Returns an empty object when there is no
+/// config file, along with a warning if it couldn't be parsed.
+pub fn read_config () -> (Value, Option<String>) {
+    let empty = Value::Object(Default::default());
+
+    let Some(path) = config_path() else {
+        return (empty, None);
+    };
+    let Ok(config_txt) = fs::read_to_string(&path) else {
+        return (empty, None);
+    };
+
+    match serde_json::from_str::<Value>(&config_txt) {
+        Ok(v) if v.is_object() => (v, None),
+        Ok(_) => (empty, Some(format!("Config file {} should contain an object.", path.display()))),
+        Err(e) => (empty, Some(format!("Failed to parse {}: {}", path.display(), e))),
+    }
+}
+
+/// `~/` is the home directory & relative paths start from the config
+/// directory.
+fn resolve (path: &str) -> String {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home) = env::var_os("HOME") {
+        return PathBuf::from(home).join(rest).display().to_string();
+    }
+
+    match config_dir() {
+        Some(dir) if PathBuf::from(path).is_relative() => dir.join(path).display().to_string(),
+        _ => path.to_owned(),
+    }
+}
+
+/// Defaults from the config file(`interval`, `quote`, `export`,
+/// `multiline`, `theme`, `color`, `conflicts`, `sort`, `pad`, `fill`,
+/// `spinner` & `sources`), along with warnings for the invalid ones.
+pub fn defaults (user_config: &Value) -> (SpinnConfig, Vec<String>) {
+    let mut defaults = SpinnConfig::default();
+    let mut warnings = Vec::new();
+
+    for key in DEFAULTS {
+        let value = match user_config.get(key) {
+            None | Some(Value::Null) => continue,
+            Some(Value::String(v)) => v.to_owned(),
+            Some(v @ (Value::Number(_) | Value::Bool(_))) => v.to_string(),
+            Some(v) => {
+                warnings.push(format!("Invalid value {} for `{}` in the config file.", v, key));
+                continue;
+            },
+        };
+
+        if let Err(error) = args::set(&mut defaults, key, &value) {
+            warnings.push(format!("Invalid value for `{}` in the config file: {}.", key, error));
+        }
+    }
+
+    match user_config.get("spinner") {
+        None | Some(Value::Null) => {},
+        Some(Value::String(name)) => defaults.pick = Some(name.to_owned()),
+        Some(v) => warnings.push(format!("Invalid value {} for `spinner` in the config file.", v)),
+    }
+
+    match user_config.get("sources") {
+        None | Some(Value::Null) => {},
+        Some(Value::Array(list)) => {
+            for item in list {
+                match item.as_str() {
+                    Some(path) => defaults.sources.push(resolve(path)),
+                    None => warnings.push(format!("Invalid source {} in the config file.", item)),
+                }
+            }
+        },
+        Some(v) => warnings.push(format!("Invalid value {} for `sources` in the config file, expected a list of paths.", v)),
+    }
+
+    (defaults, warnings)
+}
@@ -0,0 +1,709 @@
+// Spinner editor
+use crossterm::{
+    cursor::{self, MoveUp},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
+    execute,
+    terminal::{Clear, ClearType}
+};
+use std::io::stdout;
+
+use crate::{json, keymap::{Action, Keymap, Scope}, layout, render::{Renderer, Terminal}, term, theme::Theme, ticker::Ticker, width};
+
+/// Number of lines drawn by the editor.
+const HEIGHT: u16 = 13;
+/// Number of frames visible in the frame list.
+const LIST_ROWS: usize = 8;
+/// Offset of the frame list from the top of the editor.
+const LIST_TOP: u16 = 2;
+/// Offset of the status line from the top of the editor.
+const STATUS_TOP: u16 = LIST_TOP + LIST_ROWS as u16 + 1;
+
+/// Snapshot of the frames used for undo/redo.
+type History = (Vec<String>, usize);
+
+pub struct Editor {
+    name: String,
+    source: Option<String>,
+    interval: u32,
+
+    frames: Vec<String>,
+    /// Frames as they were when last saved(or loaded).
+    saved: Vec<String>,
+    /// Whether the spinner was saved at least once.
+    has_saved: bool,
+
+    cursor: usize,
+    scroll: usize,
+    anim: usize,
+    /// Row where the editor starts, used for mouse events.
+    top: u16,
+    terminal: Terminal,
+
+    /// Text & caret position of the frame being edited.
+    input: Option<(String, usize)>,
+    /// Whether the frame being edited was added by `insert`.
+    inserted: bool,
+
+    undo: Vec<History>,
+    redo: Vec<History>,
+
+    message: String,
+    confirm_quit: bool,
+
+    keymap: Keymap,
+    theme: Theme,
+}
+
+impl Editor {
+    pub fn new (name: &str, frames: &[String], source: Option<String>, interval: u32, keymap: Keymap, theme: Theme) -> Editor {
+        Editor {
+            name: name.to_owned(),
+            source,
+            interval,
+
+            frames: frames.to_vec(),
+            saved: frames.to_vec(),
+            has_saved: false,
+
+            cursor: 0,
+            scroll: 0,
+            anim: 0,
+            top: 0,
+            terminal: Terminal::default(),
+
+            input: None,
+            inserted: false,
+
+            undo: Vec::new(),
+            redo: Vec::new(),
+
+            message: String::new(),
+            confirm_quit: false,
+
+            keymap,
+            theme,
+        }
+    }
+
+    fn is_dirty (&self) -> bool {
+        self.frames != self.saved
+    }
+
+    /// Frames shown in the live preview(includes the frame being edited).
+    fn preview_frames (&self) -> Vec<String> {
+        let mut frames = self.frames.clone();
+
+        if let Some((text, _)) = &self.input && self.cursor < frames.len() {
+            frames[self.cursor] = text.to_owned();
+        }
+
+        frames
+    }
+
+    //|fS "chunk: History"
+
+    fn checkpoint (&mut self) {
+        self.undo.push((self.frames.clone(), self.cursor));
+        self.redo.clear();
+    }
+
+    fn undo (&mut self) {
+        match self.undo.pop() {
+            Some((frames, cursor)) => {
+                self.redo.push((std::mem::replace(&mut self.frames, frames), self.cursor));
+                self.cursor = cursor;
+                self.message = "Undone".to_owned();
+            },
+            None => {
+                self.message = "Nothing to undo".to_owned();
+            }
+        }
+    }
+
+    fn redo (&mut self) {
+        match self.redo.pop() {
+            Some((frames, cursor)) => {
+                self.undo.push((std::mem::replace(&mut self.frames, frames), self.cursor));
+                self.cursor = cursor;
+                self.message = "Redone".to_owned();
+            },
+            None => {
+                self.message = "Nothing to redo".to_owned();
+            }
+        }
+    }
+
+    //|fE
+
+    //|fS "chunk: Frame operations"
+
+    fn insert (&mut self, after: bool) {
+        self.checkpoint();
+
+        let at = if self.frames.is_empty() {
+            0
+        } else if after {
+            self.cursor + 1
+        } else {
+            self.cursor
+        };
+
+        self.frames.insert(at, String::new());
+        self.cursor = at;
+        self.input = Some((String::new(), 0));
+        self.inserted = true;
+    }
+
+    fn delete (&mut self) {
+        if self.frames.is_empty() {
+            return;
+        }
+
+        self.checkpoint();
+        self.frames.remove(self.cursor);
+
+        if self.cursor >= self.frames.len() && self.cursor > 0 {
+            self.cursor -= 1;
+        }
+    }
+
+    fn duplicate (&mut self) {
+        if self.frames.is_empty() {
+            return;
+        }
+
+        self.checkpoint();
+
+        let copy = self.frames[self.cursor].to_owned();
+        self.frames.insert(self.cursor + 1, copy);
+        self.cursor += 1;
+    }
+
+    fn move_frame (&mut self, down: bool) {
+        if down && self.cursor + 1 < self.frames.len() {
+            self.checkpoint();
+            self.frames.swap(self.cursor, self.cursor + 1);
+            self.cursor += 1;
+        } else if !down && self.cursor > 0 && !self.frames.is_empty() {
+            self.checkpoint();
+            self.frames.swap(self.cursor, self.cursor - 1);
+            self.cursor -= 1;
+        }
+    }
+
+    fn save (&mut self) {
+        let Some(path) = &self.source else {
+            self.message = "No --source file to save to!".to_owned();
+            return;
+        };
+
+        if self.frames.is_empty() {
+            self.message = "Can't save a spinner without frames!".to_owned();
+            return;
+        }
+
+        match json::save_spinner(path, &self.name, &self.frames) {
+            Ok(_) => {
+                self.saved = self.frames.clone();
+                self.has_saved = true;
+                self.message = format!("Saved to {}", path);
+            },
+            Err(e) => {
+                self.message = format!("Failed to save: {}", e);
+            }
+        }
+    }
+
+    //|fE
+
+    //|fS "chunk: Input"
+
+    /// Handles a key while editing a frame.
+    fn edit_key (&mut self, key: KeyEvent) {
+        let Some((text, caret)) = self.input.as_mut() else {
+            return;
+        };
+
+        // The caret moves by graphemes(escape sequences count as one).
+        let byte_at = |text: &str, n: usize| -> usize {
+            width::segments(text).iter().take(n).map(|s| s.text().len()).sum()
+        };
+        let count = |text: &str| -> usize { width::segments(text).len() };
+
+        match key.code {
+            KeyCode::Enter => {
+                let text = text.to_owned();
+
+                if self.inserted {
+                    self.frames[self.cursor] = text;
+                } else if self.frames.get(self.cursor) != Some(&text) {
+                    self.checkpoint();
+                    self.frames[self.cursor] = text;
+                }
+
+                self.input = None;
+                self.inserted = false;
+            },
+            KeyCode::Esc => {
+                // Drop the empty frame added by `insert`.
+                if self.inserted && let Some((frames, cursor)) = self.undo.pop() {
+                    self.frames = frames;
+                    self.cursor = cursor;
+                }
+
+                self.input = None;
+                self.inserted = false;
+            },
+            KeyCode::Backspace if *caret > 0 => {
+                *caret -= 1;
+                let at = byte_at(text, *caret)..byte_at(text, *caret + 1);
+                text.replace_range(at, "");
+            },
+            KeyCode::Delete if *caret < count(text) => {
+                let at = byte_at(text, *caret)..byte_at(text, *caret + 1);
+                text.replace_range(at, "");
+            },
+            KeyCode::Left if *caret > 0 => {
+                *caret -= 1;
+            },
+            KeyCode::Right if *caret < count(text) => {
+                *caret += 1;
+            },
+            KeyCode::Home => {
+                *caret = 0;
+            },
+            KeyCode::End => {
+                *caret = count(text);
+            },
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let at = byte_at(text, *caret);
+                text.insert(at, c);
+
+                // Combining marks join the grapheme before the caret.
+                *caret = count(&text[..at + c.len_utf8()]);
+            },
+            _ => {}
+        }
+    }
+
+    /// Handles a key in the frame list. Returns `true` when the editor
+    /// should be closed.
+    fn list_key (&mut self, key: KeyEvent) -> bool {
+        let action = self.keymap.action(Scope::Editor, &key);
+
+        if action == Some(Action::Quit) {
+            if self.is_dirty() && !self.confirm_quit {
+                let quit_key = self.keymap.keys(Scope::Editor, Action::Quit).first().map(|k| k.to_string()).unwrap_or_default();
+
+                self.confirm_quit = true;
+                self.message = format!("Unsaved changes! Press {} again to discard them.", quit_key);
+                return false;
+            }
+
+            return true;
+        }
+
+        self.confirm_quit = false;
+        self.message = String::new();
+
+        let last = self.frames.len().saturating_sub(1);
+
+        match action {
+            Some(Action::FrameDown) => {
+                self.cursor = (self.cursor + 1).min(last);
+            },
+            Some(Action::FrameUp) => {
+                self.cursor = self.cursor.saturating_sub(1);
+            },
+            Some(Action::FramePageDown) => {
+                self.cursor = (self.cursor + LIST_ROWS).min(last);
+            },
+            Some(Action::FramePageUp) => {
+                self.cursor = self.cursor.saturating_sub(LIST_ROWS);
+            },
+            Some(Action::FrameFirst) => {
+                self.cursor = 0;
+            },
+            Some(Action::FrameLast) => {
+                self.cursor = last;
+            },
+            Some(Action::MoveDown) => self.move_frame(true),
+            Some(Action::MoveUp) => self.move_frame(false),
+            Some(Action::InsertAfter) => self.insert(true),
+            Some(Action::InsertBefore) => self.insert(false),
+            Some(Action::Delete) => self.delete(),
+            Some(Action::Duplicate) => self.duplicate(),
+            Some(Action::Undo) => self.undo(),
+            Some(Action::Redo) => self.redo(),
+            Some(Action::Save) => self.save(),
+            Some(Action::Faster) if self.interval > 100 => {
+                self.interval -= 50;
+            },
+            Some(Action::Slower) if self.interval < 1000 => {
+                self.interval += 50;
+            },
+            Some(Action::EditFrame) if !self.frames.is_empty() => {
+                let text = self.frames[self.cursor].to_owned();
+                let caret = width::segments(&text).len();
+
+                self.input = Some((text, caret));
+            },
+            _ => {}
+        }
+
+        false
+    }
+
+    /// Handles mouse events. Returns `true` if the editor should be redrawn.
+    fn mouse (&mut self, mouse: MouseEvent) -> bool {
+        let terminal_w: u16 = layout::terminal_width();
+        let list_w = if terminal_w < layout::COMPACT_WIDTH { terminal_w } else { terminal_w / 2 };
+
+        let list_rows = self.top + LIST_TOP..self.top + LIST_TOP + LIST_ROWS as u16;
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if self.input.is_none() && list_rows.contains(&mouse.row) && mouse.column < list_w => {
+                let index = self.scroll + (mouse.row - list_rows.start) as usize;
+
+                if index >= self.frames.len() {
+                    return false;
+                }
+
+                self.cursor = index;
+                self.confirm_quit = false;
+                true
+            },
+            // Scrubbing through the preview with the status line.
+            MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left) if mouse.row == self.top + STATUS_TOP => {
+                if self.frames.is_empty() {
+                    return false;
+                }
+
+                self.anim = (mouse.column as usize * self.frames.len() / terminal_w.max(1) as usize).min(self.frames.len() - 1);
+                true
+            },
+            MouseEventKind::ScrollUp if self.interval < 1000 => {
+                self.interval += 50;
+                true
+            },
+            MouseEventKind::ScrollDown if self.interval > 100 => {
+                self.interval -= 50;
+                true
+            },
+            _ => false
+        }
+    }
+
+    //|fE
+
+    //|fS "chunk: Drawing"
+
+    fn clear_output (&self) {
+        execute!(
+            stdout(),
+
+            MoveUp(HEIGHT),
+            Clear(ClearType::FromCursorDown),
+        ).ok();
+    }
+
+    fn draw_row (&self, r: &mut dyn Renderer, y: usize, index: usize, list_w: usize) {
+        let t = &self.theme;
+        let number_size = self.frames.len().to_string().chars().count();
+        let text_size = list_w.saturating_sub(number_size + 6);
+
+        if index >= self.frames.len() {
+            if index == 0 && list_w < 36 {
+                r.print(3, y, &layout::truncate("No frames, press o to insert one.", list_w.saturating_sub(3)), &t.text);
+            } else if index == 0 {
+                let x = r.print(3, y, "No frames, press ", &t.text);
+                let x = r.print(x, y, "o", &t.key.bold());
+                r.print(x, y, " to insert one.", &t.text);
+            }
+
+            return;
+        }
+
+        let selected = index == self.cursor;
+
+        let mut x = r.print(1, y, if selected { "󰁔" } else { " " }, &t.accent);
+        x = r.print(x + 1, y, &format!("{:>number_size$}", index + 1), &if selected { t.value.bold() } else { t.muted });
+        x = r.print(x + 1, y, "│", &t.muted) + 1;
+
+        match &self.input {
+            Some((text, caret)) if selected => {
+                let segments = width::segments(text);
+
+                // The caret is a space at the end(or on an escape sequence).
+                let at = segments.get(*caret).filter(|s| s.width() > 0).map(|s| s.text()).unwrap_or(" ");
+                let mut used = width::width(at);
+
+                // Scroll the text so that the caret stays visible(even
+                // when there's no room for the text).
+                let mut start = *caret;
+
+                while start > 0 && used + segments[start - 1].width() <= text_size {
+                    start -= 1;
+                    used += segments[start].width();
+                }
+
+                let before: String = segments[start..*caret].iter().map(|s| s.text()).collect();
+                let after: String = segments.iter().skip(*caret + 1).map(|s| s.text()).collect();
+
+                x = r.print(x, y, &before, &t.text);
+                x = r.print(x, y, at, &t.text.reverse());
+                r.print(x, y, &width::truncate(&after, text_size.saturating_sub(used)), &t.text);
+            },
+            _ => {
+                r.print(x, y, &layout::fit(&term::adapt(&self.frames[index]), text_size), &if selected { t.text.bold() } else { t.text });
+            },
+        }
+    }
+
+    fn draw (&self, r: &mut dyn Renderer) {
+        let t = &self.theme;
+        let terminal_w = r.size().0;
+        let compact = terminal_w < layout::COMPACT_WIDTH as usize;
+
+        // The compact layout shows the preview below the header.
+        let list_w = if compact { terminal_w } else { terminal_w / 2 };
+        let preview_w = if compact { terminal_w } else { terminal_w.saturating_sub(list_w) };
+
+        let preview = self.preview_frames();
+        let preview_frame = match preview.len() {
+            0 => String::new(),
+            n => layout::truncate(&term::adapt(&preview[self.anim % n]), preview_w),
+        };
+
+        let header_size = width::width(" 󰏫 Editing: ") + width::width(" [+]");
+
+        let mut x = r.print(1, 0, "󰏫", &t.accent);
+        x = r.print(x + 1, 0, "Editing: ", &t.text);
+        x = r.print(x, 0, &layout::truncate(&self.name, terminal_w.saturating_sub(header_size)), &t.title.bold());
+
+        if self.is_dirty() {
+            r.print(x, 0, " [+]", &t.warning);
+        }
+
+        if compact {
+            r.print(0, 1, &width::center(&preview_frame, preview_w), &t.spinner);
+        }
+
+        for row in 0..LIST_ROWS {
+            self.draw_row(r, LIST_TOP as usize + row, self.scroll + row, list_w);
+        }
+
+        if !compact {
+            r.print(list_w, LIST_TOP as usize + LIST_ROWS / 2 - 1, &width::center(&preview_frame, preview_w), &t.spinner);
+        }
+
+        let y = STATUS_TOP as usize;
+        let frame_size = self.frames.len().to_string().chars().count();
+        let current = if self.frames.is_empty() { 0 } else { self.cursor + 1 };
+
+        let stat = format!(" 󰄉 Interval: {}ms • 󰕟 Frame: {:0frame_size$}/{}", self.interval, current, self.frames.len());
+        let short_stat = format!(" 󰄉 {}ms • 󰕟 {:0frame_size$}/{}", self.interval, current, self.frames.len());
+
+        let short = width::width(&stat) + 3 + width::width(&self.message) > terminal_w;
+        let stat_len = if short { width::width(&short_stat) } else { width::width(&stat) };
+
+        if stat_len > terminal_w {
+            r.print(0, y, &layout::truncate(&short_stat, terminal_w), &t.text);
+        } else {
+            let mut x = r.print(1, y, "󰄉 ", &t.title.bold());
+            x = r.print(x, y, if short { "" } else { "Interval: " }, &t.text);
+            x = r.print(x, y, &format!("{}ms", self.interval), &t.value);
+            x = r.print(x, y, " •", &t.text);
+
+            x = r.print(x + 1, y, "󰕟 ", &t.accent.bold());
+            x = r.print(x, y, if short { "" } else { "Frame: " }, &t.text);
+            x = r.print(x, y, &format!("{:0frame_size$}/{}", current, self.frames.len()), &t.value);
+
+            if !self.message.is_empty() && stat_len + 3 < terminal_w {
+                x = r.print(x, y, " • ", &t.text);
+                r.print(x, y, &layout::truncate(&self.message, terminal_w - stat_len - 3), &t.warning);
+            }
+        }
+
+        let y = STATUS_TOP as usize + 1;
+        let keys: Vec<(String, &str)> = if self.input.is_some() {
+            vec![ ("Enter".to_owned(), "Apply"), ("Esc".to_owned(), "Cancel") ]
+        } else {
+            self.keymap.footer(Scope::Editor)
+        };
+
+        // Only show the keymaps that fit.
+        let keys_text: Vec<String> = keys.iter().map(|(key, action)| format!(" {}: {}", key, action)).collect();
+        let key_count = layout::fitting(&keys_text, ",", terminal_w.saturating_sub(width::width(" 󰌏 ") + 2));
+
+        if key_count > 0 {
+            let mut x = r.print(1, y, "󰌏 ", &t.title);
+
+            for (k, (key, action)) in keys.iter().take(key_count).enumerate() {
+                x = r.print(x + 1, y, key, &t.key.bold());
+                x = r.print(x, y, ": ", &t.text);
+                x = r.print(x, y, action, &t.accent);
+                x = r.print(x, y, if k + 1 < key_count { "," } else { "" }, &t.text);
+            }
+
+            if key_count < keys.len() {
+                r.print(x, y, " …", &t.muted);
+            }
+        }
+    }
+
+    fn render (&mut self) {
+        let mut terminal = std::mem::take(&mut self.terminal);
+
+        terminal.begin(layout::terminal_width() as usize, HEIGHT as usize, self.top);
+        self.draw(&mut terminal);
+        terminal.flush();
+
+        self.terminal = terminal;
+    }
+
+    /// Keeps the cursor inside the visible part of the frame list.
+    fn fix_scroll (&mut self) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + LIST_ROWS {
+            self.scroll = self.cursor + 1 - LIST_ROWS;
+        }
+    }
+
+    fn redraw (&mut self) {
+        self.fix_scroll();
+        self.render();
+    }
+
+    //|fE
+
+    /// Runs the editor until it is closed. Returns the last saved frames
+    /// if the spinner was saved.
+    pub fn run (&mut self) -> Option<Vec<String>> {
+        for _ in 0..HEIGHT {
+            println!();
+        }
+
+        self.clear_output();
+        self.top = cursor::position().map(|p| p.1).unwrap_or(0);
+        self.render();
+
+        // The preview is animated on its own schedule, input only redraws it.
+        let mut ticker = Ticker::new(self.interval);
+
+        loop {
+            //|fS "chunk: Editor loop"
+
+            if event::poll(ticker.remaining()).unwrap() {
+                match event::read().unwrap() {
+                    Event::Key(key) => {
+                        if self.input.is_some() {
+                            self.edit_key(key);
+                        } else if self.list_key(key) {
+                            self.clear_output();
+                            break;
+                        }
+
+                        self.redraw();
+                    },
+                    Event::Resize(_, _) => {
+                        self.clear_output();
+                        self.top = cursor::position().map(|p| p.1).unwrap_or(0);
+                        self.terminal.invalidate();
+
+                        self.redraw();
+                    },
+                    Event::Mouse(mouse) if self.mouse(mouse) => {
+                        self.redraw();
+                    },
+                    _ => {}
+                }
+            }
+
+            if ticker.due(self.interval) {
+                self.anim += 1;
+                self.redraw();
+            }
+
+            //|fE
+        }
+
+        if self.has_saved {
+            Some(self.saved.clone())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Buffer;
+
+    fn editor (frames: &[&str]) -> Editor {
+        let frames: Vec<String> = frames.iter().map(|f| f.to_string()).collect();
+        Editor::new("clock", &frames, None, 100, Keymap::from_config(None).0, Theme::from_config("dark", None).0)
+    }
+
+    #[test]
+    fn draw_fits_the_width () {
+        for frames in [ &["時計", "計時"][..], &[] ] {
+            let mut e = editor(frames);
+
+            for w in [ 20, 40, 80 ] {
+                let mut buffer = Buffer::new(w, HEIGHT as usize);
+                e.draw(&mut buffer);
+
+                let lines: Vec<String> = buffer.to_string().lines().map(|l| l.to_owned()).collect();
+                assert!(lines.iter().all(|l| width::width(l) == w), "{:?}", lines);
+                assert!(lines[0].contains("Editing: "), "{:?}", lines);
+            }
+
+            // The caret is shown even without room for the text.
+            e.insert(true);
+
+            let mut buffer = Buffer::new(4, HEIGHT as usize);
+            e.draw(&mut buffer);
+        }
+    }
+
+    fn key (e: &mut Editor, code: KeyCode) {
+        e.edit_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn edit_by_graphemes () {
+        let mut e = editor(&["a👩‍💻b"]);
+        e.input = Some(("a👩‍💻b".to_owned(), 2));
+
+        key(&mut e, KeyCode::Backspace);
+        assert_eq!(e.input, Some(("ab".to_owned(), 1)));
+
+        // A combining mark joins the grapheme before the caret.
+        key(&mut e, KeyCode::Char('\u{301}'));
+        assert_eq!(e.input, Some(("a\u{301}b".to_owned(), 1)));
+
+        key(&mut e, KeyCode::Left);
+        key(&mut e, KeyCode::Delete);
+        assert_eq!(e.input, Some(("b".to_owned(), 0)));
+    }
+
+    #[test]
+    fn wide_input_stays_in_the_list () {
+        let mut e = editor(&["x"]);
+        let text = "時計".repeat(20);
+        e.input = Some((text.to_owned(), text.chars().count()));
+
+        // The list takes the left half, the preview starts at column 40.
+        let mut buffer = Buffer::new(80, HEIGHT as usize);
+        e.draw(&mut buffer);
+
+        let y = LIST_TOP as usize;
+        let row = buffer.to_string().lines().nth(y).unwrap().to_owned();
+
+        assert!((40..80).all(|x| buffer.get(x, y).unwrap().symbol == " "), "{:?}", row);
+        assert!(row.contains("時計"), "{:?}", row);
+    }
+}
@@ -5,77 +5,69 @@ pub enum SpinnExport {
     String,
 }
 
-pub fn export (export_as: &SpinnExport, quote: &char, multiline: &bool, frames: &Vec<String>) {
+/// Text printed for `--export`.
+pub fn render (export_as: &SpinnExport, quote: &char, multiline: &bool, frames: &[String]) -> String {
     match export_as {
-        SpinnExport::List => {
-            as_list(multiline, quote, frames);
-        },
-        SpinnExport::Array => {
-            as_array(multiline, quote, frames);
-        },
-        SpinnExport::String => {
-            as_string(multiline, quote, frames);
-        }
-    };
-
-    println!("");
+        SpinnExport::List => as_list(multiline, quote, frames),
+        SpinnExport::Array => as_array(multiline, quote, frames),
+        SpinnExport::String => as_string(multiline, quote, frames),
+    }
 }
 
-pub fn as_list (multiline: &bool, quote: &char, frames: &Vec<String>) {
+pub fn as_list (multiline: &bool, quote: &char, frames: &[String]) -> String {
+    let mut output = String::new();
+
     if multiline == &true {
-        println!("[");
+        output += "[\n";
     } else {
-        print!("[ ");
+        output += "[ ";
     }
 
     for (f, frame) in frames.iter().enumerate() {
         if multiline == &true {
-            println!("\t{}{}{},", quote, frame, quote);
+            output += &format!("\t{}{}{},\n", quote, frame, quote);
         } else if f < frames.len() - 1 {
-            print!("{}{}{}, ", quote, frame, quote);
+            output += &format!("{}{}{}, ", quote, frame, quote);
         } else {
-            print!("{}{}{} ", quote, frame, quote);
+            output += &format!("{}{}{} ", quote, frame, quote);
         }
     }
 
-    if multiline == &true {
-        println!("]");
-    } else {
-        print!("]\n");
-    }
+    output + "]\n"
 }
 
-pub fn as_array (multiline: &bool, quote: &char, frames: &Vec<String>) {
+pub fn as_array (multiline: &bool, quote: &char, frames: &[String]) -> String {
+    let mut output = String::new();
+
     if multiline == &true {
-        println!("{{");
+        output += "{\n";
     } else {
-        print!("{{ ");
+        output += "{ ";
     }
 
     for (f, frame) in frames.iter().enumerate() {
         if multiline == &true {
-            println!("\t{}{}{},", quote, frame, quote);
+            output += &format!("\t{}{}{},\n", quote, frame, quote);
         } else if f < frames.len() - 1 {
-            print!("{}{}{}, ", quote, frame, quote);
+            output += &format!("{}{}{}, ", quote, frame, quote);
         } else {
-            print!("{}{}{} ", quote, frame, quote);
+            output += &format!("{}{}{} ", quote, frame, quote);
         }
     }
 
-    if multiline == &true {
-        println!("}}");
-    } else {
-        print!("}}\n");
-    }
+    output + "}\n"
 }
 
-pub fn as_string (multiline: &bool, _quote: &char, frames: &Vec<String>) {
+pub fn as_string (multiline: &bool, _quote: &char, frames: &[String]) -> String {
+    let mut output = String::new();
+
     for frame in frames {
         if multiline == &true {
-            println!("{}", frame);
+            output += &format!("{}\n", frame);
         } else {
-            print!("{} ", frame);
+            output += &format!("{} ", frame);
         }
     }
-}
 
+    output
+}
@@ -0,0 +1,331 @@
+// Fullscreen preview on the alternate screen
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, MouseButton, MouseEvent, MouseEventKind},
+    execute,
+    terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen}
+};
+use std::io::stdout;
+
+use crate::{export, keymap::{Action, Scope}, layout, render::{Renderer, Terminal}, term::{self, Style}, ticker::Ticker, width, Spinn};
+
+/// Widest the spinner list gets.
+const SIDEBAR_WIDTH: usize = 28;
+/// The spinner list is hidden below this width.
+const SIDEBAR_MIN_WIDTH: u16 = 60;
+/// The export preview is hidden below this height.
+const EXPORT_MIN_HEIGHT: u16 = 18;
+
+impl Spinn {
+    /// Draws the fullscreen preview on `terminal`.
+    fn render_fullscreen (&self, terminal: &mut Terminal) {
+        let (terminal_w, terminal_h) = layout::terminal_size();
+
+        terminal.begin(terminal_w as usize, terminal_h as usize, 0);
+        self.draw_fullscreen(terminal);
+        terminal.flush();
+    }
+
+    fn sidebar_width (&self, terminal_w: u16) -> usize {
+        if terminal_w < SIDEBAR_MIN_WIDTH {
+            0
+        } else {
+            (terminal_w as usize / 3).min(SIDEBAR_WIDTH)
+        }
+    }
+
+    /// First spinner shown in a sidebar with `rows` rows. Keeps the
+    /// selected spinner in the middle.
+    fn sidebar_start (&self, keys: &[String], rows: usize) -> usize {
+        let selected = keys.iter().position(|k| k == &self.pick).unwrap_or(0);
+        selected.saturating_sub(rows / 2).min(keys.len().saturating_sub(rows))
+    }
+
+    //|fS "chunk: Drawing"
+
+    fn draw_fullscreen (&self, r: &mut dyn Renderer) {
+        let t = &self.theme;
+        let (w, h) = r.size();
+
+        let body_h = h.saturating_sub(3);
+
+        let sidebar_w = self.sidebar_width(w as u16);
+        let main_x = if sidebar_w > 0 { sidebar_w + 1 } else { 0 };
+        let main_w = w.saturating_sub(main_x);
+
+        let frames = self.frames(&self.pick);
+        let max = frames.len();
+        let frame_size = max.to_string().chars().count();
+
+        // Header
+        let x = r.print(1, 0, "󰢵 ", &t.accent);
+        let name_end = r.print(x, 0, env!("CARGO_PKG_NAME"), &t.title.bold());
+
+        // The sort mode is only shown when it doesn't cover the name.
+        let sort = format!("󰒺 Sort: {} ", self.sort.name());
+
+        if name_end + 1 + width::width(&sort) <= w {
+            let x = r.print(w - width::width(&sort), 0, "󰒺 Sort: ", &t.text);
+            r.print(x, 0, self.sort.name(), &t.value);
+        }
+
+        r.print(0, 1, &"─".repeat(w), &t.muted);
+
+        // Sidebar
+        if sidebar_w > 0 {
+            let keys = &self.keys;
+            let start = self.sidebar_start(keys, body_h);
+
+            for row in 0..body_h {
+                if let Some(name) = keys.get(start + row) {
+                    let favorite = if self.state.is_favorite(name) { "󰓎" } else { " " };
+                    let text = layout::fit(&format!(" {} {}", favorite, name), sidebar_w);
+
+                    if name == &self.pick {
+                        r.print(0, row + 2, &text, &t.accent.reverse());
+                    } else {
+                        r.print(0, row + 2, &text, &t.text);
+                    }
+                }
+
+                r.print(sidebar_w, row + 2, "│", &t.muted);
+            }
+        }
+
+        let mut lines: Vec<(Style, String)> = Vec::new();
+
+        // Name & preview
+        let favorite = if self.state.is_favorite(&self.pick) { " 󰓎" } else { "" };
+        let export_h = if h >= EXPORT_MIN_HEIGHT as usize { body_h / 3 } else { 0 };
+        let preview_h = body_h.saturating_sub(1 + 8 + export_h);
+
+        lines.push((t.title.bold(), format!(" {}{}", self.pick, favorite)));
+
+        for row in 0..preview_h {
+            if row == preview_h / 2 {
+                let current = layout::truncate(&term::adapt(frames.get(self.frame).map(|f| f.as_str()).unwrap_or_default()), main_w);
+                lines.push((t.spinner, width::center(&current, main_w)));
+            } else {
+                lines.push((Style::default(), String::new()));
+            }
+        }
+
+        // Metadata
+        let frame_w = frames.iter().map(|f| width::width(f)).max().unwrap_or(0);
+
+        lines.push((t.muted, " ".to_owned() + &"─".repeat(main_w.saturating_sub(2))));
+        lines.push((t.text, format!(" 󰄉 Interval  {}ms", self.interval)));
+        lines.push((t.text, format!(" 󰕟 Current   {:0frame_size$}/{}", self.frame + 1, max)));
+        lines.push((t.text, format!(" 󰕬 Frames    {}", max)));
+        lines.push((t.text, format!(" 󰆾 Width     {}", frame_w)));
+        lines.push((t.text, format!(" 󰉶 Pad       {}", self.pad_name())));
+        lines.push((t.text, format!(" 󰏗 Pack      {}", self.meta.get(&self.pick).map(|m| m.pack.as_str()).unwrap_or_default())));
+        lines.push((t.muted, " ".to_owned() + &"─".repeat(main_w.saturating_sub(2))));
+
+        // Export preview
+        if export_h > 0 {
+            let export_as = self.export.as_ref().unwrap_or(&export::SpinnExport::List);
+            let output = export::render(export_as, &self.quote, self.multi_line.as_ref().unwrap_or(&true), &frames);
+
+            lines.push((t.accent, format!(" 󰈇 Export({:?})", export_as)));
+
+            for line in output.lines().take(export_h.saturating_sub(1)) {
+                lines.push((t.value, "   ".to_owned() + &line.replace('\t', "    ")));
+            }
+        }
+
+        for (row, (style, text)) in lines.iter().take(body_h).enumerate() {
+            r.print(main_x, row + 2, &layout::truncate(text, main_w), style);
+        }
+
+        // Keymaps
+        let keymap = self.keymap.footer(Scope::Preview);
+        let keymap_text: Vec<String> = keymap.iter().map(|(key, label)| format!("{}: {}", key, label)).collect();
+        let keymap_count = layout::fitting(&keymap_text, ", ", w.saturating_sub(width::width("󰌏  ") + 2));
+
+        if self.search.is_some() {
+            self.draw_search(r, 1, h.saturating_sub(1));
+        } else if keymap_count > 0 {
+            let row = h.saturating_sub(1);
+            let mut x = r.print(1, row, "󰌏  ", &t.title);
+
+            for (k, (key, label)) in keymap.iter().take(keymap_count).enumerate() {
+                x = r.print(x, row, key, &t.value.bold());
+                x = r.print(x, row, ": ", &t.text);
+                x = r.print(x, row, label, &t.accent);
+
+                if k + 1 < keymap_count {
+                    x = r.print(x, row, ", ", &t.text);
+                }
+            }
+
+            if keymap_count < keymap.len() {
+                r.print(x, row, " …", &t.muted);
+            }
+        }
+    }
+
+    //|fE
+
+    /// Handles mouse events in the fullscreen preview. Returns `true`
+    /// if it should be redrawn.
+    fn fullscreen_mouse (&mut self, mouse: MouseEvent) -> bool {
+        let (terminal_w, terminal_h) = layout::terminal_size();
+        let sidebar_w = self.sidebar_width(terminal_w);
+        let on_sidebar = (mouse.column as usize) < sidebar_w;
+        let body_h = (terminal_h as usize).saturating_sub(3);
+
+        match mouse.kind {
+            // Only the rows of the list, not the footer.
+            MouseEventKind::Down(MouseButton::Left) if on_sidebar && mouse.row >= 2 && (mouse.row as usize) < 2 + body_h => {
+                let keys = &self.keys;
+                let start = self.sidebar_start(keys, body_h);
+
+                let Some(name) = keys.get(start + mouse.row as usize - 2) else {
+                    return false;
+                };
+
+                self.pick = name.to_owned();
+                self.frame = 0_usize;
+                true
+            },
+            MouseEventKind::ScrollDown if on_sidebar => {
+                self.next_loader();
+                self.frame = 0_usize;
+                true
+            },
+            MouseEventKind::ScrollUp if on_sidebar => {
+                self.prev_loader();
+                self.frame = 0_usize;
+                true
+            },
+            MouseEventKind::ScrollUp if self.interval < 1000 => {
+                self.interval += 50;
+                true
+            },
+            MouseEventKind::ScrollDown if self.interval > 100 => {
+                self.interval -= 50;
+                true
+            },
+            _ => false
+        }
+    }
+
+    /// Shows the preview on the alternate screen, the original screen
+    /// is restored when it is closed.
+    pub fn fullscreen (&mut self) {
+        execute!(stdout(), EnterAlternateScreen, Hide, EnableMouseCapture, Clear(ClearType::All)).ok();
+        let mut terminal = Terminal::default();
+        self.render_fullscreen(&mut terminal);
+
+        let mut ticker = Ticker::new(self.interval);
+
+        loop {
+            //|fS "chunk: Fullscreen loop"
+
+            if event::poll(ticker.remaining()).unwrap() {
+                match event::read().unwrap() {
+                    Event::Key(key) if self.search.is_some() => self.search_key(&key),
+                    Event::Key(key) => {
+                        match self.keymap.action(Scope::Preview, &key) {
+                            Some(Action::Quit) => break,
+                            // The editor & the comparison are drawn inline.
+                            Some(Action::Edit) => {
+                                execute!(stdout(), LeaveAlternateScreen).ok();
+                                self.edit();
+                                execute!(stdout(), EnterAlternateScreen, Clear(ClearType::All)).ok();
+                                terminal.invalidate();
+
+                                self.frame = 0_usize;
+                                ticker.reset(self.interval);
+                            },
+                            Some(Action::Compare) => {
+                                execute!(stdout(), LeaveAlternateScreen).ok();
+                                self.compare();
+                                execute!(stdout(), EnterAlternateScreen, Clear(ClearType::All)).ok();
+                                terminal.invalidate();
+
+                                ticker.reset(self.interval);
+                            },
+                            Some(action) if self.preview_action(action) => {},
+                            _ => continue
+                        }
+                    },
+                    Event::Resize(_, _) => {
+                        execute!(stdout(), Clear(ClearType::All)).ok();
+                        terminal.invalidate();
+                    },
+                    Event::Mouse(mouse) if self.fullscreen_mouse(mouse) => {},
+                    _ => continue
+                }
+
+                self.render_fullscreen(&mut terminal);
+            }
+
+            if ticker.due(self.interval) {
+                self.frame = (self.frame + 1) % self.items[&self.pick].len().max(1);
+                self.render_fullscreen(&mut terminal);
+            }
+
+            //|fE
+        }
+
+        execute!(stdout(), Show, DisableMouseCapture, LeaveAlternateScreen).ok();
+        self.remember();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{render::Buffer, tests::spinn, width, Spinn};
+
+    /// Lines of the fullscreen preview drawn on a `width`x`height` screen.
+    fn fullscreen (fd: &Spinn, width: usize, height: usize) -> Vec<String> {
+        let mut buffer = Buffer::new(width, height);
+        fd.draw_fullscreen(&mut buffer);
+
+        buffer.to_string().lines().map(|l| l.to_owned()).collect()
+    }
+
+    #[test]
+    fn fullscreen_fits_the_screen () {
+        let fd = spinn(&[ ("dots", &["⠋", "⠙", "⠹"]), ("line", &["-", "\\", "|", "/"]) ]);
+
+        for (w, h) in [ (20, 14), (40, 16), (80, 24) ] {
+            let lines = fullscreen(&fd, w, h);
+
+            assert_eq!(lines.len(), h);
+            assert!(lines.iter().all(|l| width::width(l) == w), "{:?}", lines);
+            assert!(lines[0].contains(env!("CARGO_PKG_NAME")), "{:?}", lines);
+            assert!(lines.iter().any(|l| l.contains("⠋")), "{:?}", lines);
+        }
+
+        // The spinner list is only shown on wide screens.
+        assert!(fullscreen(&fd, 80, 24).iter().any(|l| l.contains("line")));
+        assert!(!fullscreen(&fd, 40, 16).iter().any(|l| l.contains("line")));
+    }
+
+    #[test]
+    fn fullscreen_wide_spinner () {
+        let fd = spinn(&[ ("clock", &["時計時計", "計時計時"]) ]);
+
+        for (w, h) in [ (20, 14), (40, 16), (80, 24) ] {
+            let lines = fullscreen(&fd, w, h);
+
+            assert!(lines.iter().all(|l| width::width(l) == w), "{:?}", lines);
+            assert!(lines.iter().any(|l| l.contains("時計時計")), "{:?}", lines);
+        }
+    }
+
+    #[test]
+    fn fullscreen_empty_spinner () {
+        let fd = spinn(&[ ("empty", &[]) ]);
+
+        for (w, h) in [ (20, 10), (40, 16), (80, 24), (8, 3) ] {
+            let lines = fullscreen(&fd, w, h);
+
+            assert_eq!(lines.len(), h);
+            assert!(lines.iter().all(|l| width::width(l) == w), "{:?}", lines);
+        }
+    }
+}
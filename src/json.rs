@@ -1,35 +1,730 @@
-use std::{collections::HashMap, fs};
-use serde_json;
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{json, Value, ser::PrettyFormatter};
+use std::{collections::HashMap, fs, io, ops::Range, path::{Path, PathBuf}};
 
-type SpinnJSON = HashMap<String, Vec<String>>;
+use crate::state;
 
-pub fn read_config (source: Option<String>) -> SpinnJSON {
-    let default_path = format!("{}/spinners.json", env!("CARGO_MANIFEST_DIR"));
-    let default_config_txt: String = match fs::read_to_string(default_path) {
+/// Spinners in the order they were read.
+pub type SpinnJSON = IndexMap<String, Vec<String>>;
+
+/// Details of a spinner besides it's frames.
+#[derive(Debug, Clone, Default)]
+pub struct Meta {
+    /// Interval the spinner is meant to be shown with.
+    pub interval: Option<u32>,
+    pub tags: Vec<String>,
+    /// Other names the spinner can be picked with.
+    pub aliases: Vec<String>,
+    /// File the spinner was read from.
+    pub source: String,
+    /// Name in that file, which differs from the catalog's when it's renamed.
+    pub name: String,
+    /// Name of the file without the extension(`builtin` for the built-in
+    /// spinners), spinners can be picked with `pack:name`.
+    pub pack: String,
+}
+
+/// Problem with an entry of a spinner file, the spinner(or the invalid
+/// part of it) is skipped.
+#[derive(Debug, Clone)]
+pub struct Invalid {
+    pub source: String,
+    pub name: String,
+    /// What's wrong, e.g. `has no frames, it's skipped.`
+    pub problem: String,
+}
+
+/// What happens when 2 files have a spinner with the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Conflict {
+    /// The later spinner replaces the earlier one.
+    #[default]
+    Override,
+    KeepFirst,
+    /// The later spinner gets a `-2`, `-3`, ... suffix.
+    Rename,
+    Error,
+}
+
+/// Keys of a spinner object, like in the schema.
+const ENTRY_KEYS: [&str; 4] = [ "frames", "interval", "tags", "aliases" ];
+
+/// Spinners read from the catalog files.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    pub items: SpinnJSON,
+    pub meta: HashMap<String, Meta>,
+    /// Spinner names by alias.
+    pub aliases: HashMap<String, String>,
+    /// Problems with the files(e.g. a missing source).
+    pub warnings: Vec<String>,
+    /// Spinners with the same name as another one.
+    pub conflicts: Vec<String>,
+    /// Entries that aren't valid spinners.
+    pub invalid: Vec<Invalid>,
+    /// Catalog names of the spinners by `pack:name`, they differ when a
+    /// spinner was renamed.
+    packs: HashMap<String, String>,
+    /// Spinners replaced by(or kept out by) another with the same name,
+    /// by `pack:name`.
+    shadowed: HashMap<String, (Vec<String>, Meta)>,
+    /// Files by pack name.
+    pack_files: HashMap<String, PathBuf>,
+    policy: Conflict,
+}
+
+impl Catalog {
+    /// Reads the spinners of a file. Spinners are either a list of frames or
+    /// an object like `{ "frames": [...], "interval": 80, "tags": [...],
+    /// "aliases": [...] }`.
+    fn read_file (&mut self, path: &Path, pack: &str) {
+        let source_txt = match fs::read_to_string(path) {
+            Ok(v) => v,
+            Err(e) => {
+                self.warnings.push(format!("Failed to read {}: {}", path.display(), e));
+                return;
+            },
+        };
+        let source = match serde_json::from_str::<Value>(&source_txt) {
+            Ok(Value::Object(v)) => v,
+            Ok(_) => {
+                self.warnings.push(format!("Spinner file {} should contain an object.", path.display()));
+                return;
+            },
+            Err(e) => {
+                self.warnings.push(format!("Failed to parse {}: {}", path.display(), e));
+                return;
+            },
+        };
+
+        let strings = |value: Option<&Value>| -> Option<Vec<String>> {
+            value?.as_array()?.iter().map(|v| v.as_str().map(|v| v.to_owned())).collect()
+        };
+
+        for (name, value) in source {
+            // Editors use `$schema` to find the schema, it's not a spinner.
+            if name == "$schema" {
+                continue;
+            }
+
+            let mut problems = Vec::new();
+
+            let (frames, details) = match &value {
+                Value::Array(_) => (strings(Some(&value)), None),
+                Value::Object(entry) => (strings(entry.get("frames")), Some(entry)),
+                _ => (None, None),
+            };
+
+            if let Some(entry) = details {
+                let unknown: Vec<String> = entry.keys().filter(|key| !ENTRY_KEYS.contains(&key.as_str())).map(|key| format!("`{}`", key)).collect();
+
+                if !unknown.is_empty() {
+                    problems.push(format!("has unknown keys({}), they're ignored.", unknown.join(", ")));
+                }
+            }
+
+            if frames.is_none() {
+                problems.push(match details {
+                    Some(entry) if !entry.contains_key("frames") => "has no `frames`, it's skipped.",
+                    _ if value.is_array() || details.is_some() => "has frames that aren't strings, it's skipped.",
+                    _ => "isn't a list of frames or an object, it's skipped.",
+                }.to_owned());
+            }
+
+            // A 0ms interval would redraw the spinner as fast as possible.
+            let interval = match details.and_then(|d| d.get("interval")) {
+                Some(value) => match value.as_u64().and_then(|v| u32::try_from(v).ok()) {
+                    Some(v) if v > 0 => Some(v),
+                    _ => {
+                        problems.push(format!("has an invalid interval({}), it should be a positive number of milliseconds.", value));
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            for key in [ "tags", "aliases" ] {
+                if details.and_then(|d| d.get(key)).is_some_and(|v| strings(Some(v)).is_none()) {
+                    problems.push(format!("has `{}` that aren't a list of strings, they're ignored.", key));
+                }
+            }
+
+            for problem in problems {
+                self.invalid.push(Invalid { source: path.display().to_string(), name: name.to_owned(), problem });
+            }
+
+            let Some(frames) = frames else {
+                continue;
+            };
+
+            let meta = Meta {
+                interval,
+                tags: strings(details.and_then(|d| d.get("tags"))).unwrap_or_default(),
+                aliases: strings(details.and_then(|d| d.get("aliases"))).unwrap_or_default(),
+                source: path.display().to_string(),
+                name: name.to_owned(),
+                pack: pack.to_owned(),
+            };
+
+            let aliases = meta.aliases.clone();
+
+            // Later aliases replace earlier ones, like spinners do.
+            if let Some(name) = self.insert(name, frames, meta) {
+                for alias in aliases {
+                    self.aliases.insert(alias, name.to_owned());
+                }
+            }
+        }
+    }
+
+    /// Adds a spinner, resolving conflicts with the current policy.
+    /// Returns the name it was added as, if it was added.
+    fn insert (&mut self, name: String, frames: Vec<String>, meta: Meta) -> Option<String> {
+        let qualified = format!("{}:{}", meta.pack, name);
+
+        let Some(existing) = self.meta.get(&name) else {
+            self.packs.insert(qualified, name.to_owned());
+            self.meta.insert(name.to_owned(), meta);
+            self.items.insert(name.to_owned(), frames);
+            return Some(name);
+        };
+
+        let existing_source = existing.source.to_owned();
+        let existing_pack = existing.pack.to_owned();
+
+        match self.policy {
+            Conflict::Override | Conflict::Error => {
+                self.conflicts.push(if self.policy == Conflict::Error {
+                    format!("`{}` is in both {} and {}.", name, existing_source, meta.source)
+                } else {
+                    format!("`{}` from {} replaces the one from {}.", name, meta.source, existing_source)
+                });
+
+                // The spinner keeps the place of the one it replaces.
+                let old = (self.items.insert(name.to_owned(), frames).unwrap_or_default(), self.meta.insert(name.to_owned(), meta).unwrap_or_default());
+                let old_qualified = format!("{}:{}", existing_pack, name);
+
+                self.packs.remove(&old_qualified);
+                self.packs.insert(qualified, name.to_owned());
+                self.shadowed.insert(old_qualified, old);
+                Some(name)
+            },
+            Conflict::KeepFirst => {
+                self.conflicts.push(format!("`{}` from {} is ignored, {} already has it.", name, meta.source, existing_source));
+                self.shadowed.insert(qualified, (frames, meta));
+                None
+            },
+            Conflict::Rename => {
+                let renamed = (2..).map(|n| format!("{}-{}", name, n)).find(|n| !self.items.contains_key(n)).unwrap_or_default();
+
+                self.conflicts.push(format!("`{}` from {} is renamed to `{}`, {} already has it.", name, meta.source, renamed, existing_source));
+                self.packs.insert(qualified, renamed.to_owned());
+                self.meta.insert(renamed.to_owned(), meta);
+                self.items.insert(renamed.to_owned(), frames);
+                Some(renamed)
+            },
+        }
+    }
+
+    /// Name of the spinner `name`(or an alias) refers to. `pack:name` picks
+    /// the spinner from a specific file, even if another one replaced it.
+    pub fn resolve (&mut self, name: &str) -> Option<String> {
+        if self.items.contains_key(name) {
+            return Some(name.to_owned());
+        }
+
+        if let Some(target) = self.aliases.get(name).filter(|target| self.items.contains_key(*target)) {
+            return Some(target.to_owned());
+        }
+
+        if let Some(target) = self.packs.get(name).filter(|target| self.items.contains_key(*target)) {
+            return Some(target.to_owned());
+        }
+
+        // Replaced spinners are added back under `pack:name`.
+        let (frames, meta) = self.shadowed.remove(name)?;
+
+        self.meta.insert(name.to_owned(), meta);
+        self.items.insert(name.to_owned(), frames);
+        Some(name.to_owned())
+    }
+
+    /// Pack name of a file. Files with the same name(e.g. `a.json` &
+    /// `dir/a.json`) get a `-2`, `-3`, ... suffix like renamed spinners.
+    fn pack_name (&mut self, path: &Path) -> String {
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+        let pack = match self.pack_files.get(&stem) {
+            Some(existing) => {
+                let renamed = (2..).map(|n| format!("{}-{}", stem, n)).find(|n| !self.pack_files.contains_key(n)).unwrap_or_default();
+
+                self.warnings.push(format!("{} & {} are both pack `{}`, the later one is pack `{}`.", existing.display(), path.display(), stem, renamed));
+                renamed
+            },
+            None => stem,
+        };
+
+        self.pack_files.insert(pack.to_owned(), path.to_path_buf());
+        pack
+    }
+
+    /// Catalog of the built-in spinners, as pack `builtin`.
+    fn with_builtin (policy: Conflict) -> Self {
+        let mut catalog = Catalog { policy, ..Catalog::default() };
+        let built_in = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/spinners.json"));
+
+        if built_in.is_file() {
+            catalog.pack_files.insert("builtin".to_owned(), built_in.to_path_buf());
+            catalog.read_file(built_in, "builtin");
+        }
+
+        catalog
+    }
+
+    /// Reads a file, or every `*.json` file of a directory in the order
+    /// of their names.
+    fn read_source (&mut self, path: &Path) {
+        if !path.is_dir() {
+            let pack = self.pack_name(path);
+            self.read_file(path, &pack);
+            return;
+        }
+
+        let mut files: Vec<PathBuf> = match fs::read_dir(path) {
+            Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+            Err(e) => {
+                self.warnings.push(format!("Failed to read {}: {}", path.display(), e));
+                return;
+            },
+        };
+
+        files.retain(|file| file.is_file() && file.extension().is_some_and(|ext| ext == "json"));
+        files.sort();
+
+        for file in files {
+            let pack = self.pack_name(&file);
+            self.read_file(&file, &pack);
+        }
+    }
+}
+
+/// Directory whose `*.json` files are always read.
+pub fn spinners_dir () -> Option<PathBuf> {
+    state::data_dir().map(|dir| dir.join("spinners"))
+}
+
+/// Reads the spinners, conflicting names are handled with `policy`.
+///
+/// The order is the built-in spinners, `$XDG_DATA_HOME/spinn-rs/spinners/*.json`
+/// & `sources`.
+pub fn read_config (sources: &[String], policy: Conflict) -> Catalog {
+    let mut catalog = Catalog::with_builtin(policy);
+
+    if let Some(dir) = spinners_dir().filter(|dir| dir.is_dir()) {
+        catalog.read_source(&dir);
+    }
+
+    for source in sources {
+        catalog.read_source(Path::new(source));
+    }
+
+    catalog
+}
+
+/// JSON Schema of the spinner files, for editors to validate & complete
+/// them.
+pub fn schema () -> Value {
+    let strings = |description: &str| json!({
+        "type": "array",
+        "items": { "type": "string" },
+        "description": description,
+    });
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "spinn-rs spinners",
+        "description": "Spinners by name, either a list of frames or an object with the frames & some details.",
+        "type": "object",
+        "properties": {
+            "$schema": { "type": "string", "description": "Schema of the file, ignored by spinn-rs." },
+        },
+        "additionalProperties": { "$ref": "#/$defs/spinner" },
+        "$defs": {
+            "frames": strings("Frames of the spinner, in the order they're shown."),
+            "spinner": {
+                "oneOf": [
+                    { "$ref": "#/$defs/frames" },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "frames": { "$ref": "#/$defs/frames" },
+                            "interval": {
+                                "type": "integer",
+                                "minimum": 1,
+                                "maximum": u32::MAX,
+                                "description": "Milliseconds between frames, used when no --interval is given.",
+                            },
+                            "tags": strings("Tags `list --tag` filters the spinners with."),
+                            "aliases": strings("Other names the spinner can be picked with."),
+                        },
+                        "required": [ "frames" ],
+                        "additionalProperties": false,
+                    },
+                ],
+            },
+        },
+    })
+}
+
+/// Saves `frames` as the spinner `name` in the JSON file at `path`.
+///
+/// Only the spinner's frames are rewritten, the rest of the file is kept as
+/// is. The file is created if it doesn't exist.
+pub fn save_spinner (path: &str, name: &str, frames: &[String]) -> io::Result<()> {
+    let source_txt = match fs::read_to_string(path) {
         Ok(v) => v,
-        Err(_) => "{}".to_owned()
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
     };
-    let mut default: SpinnJSON = match serde_json::from_str(&default_config_txt) {
-        Ok(e) => e,
-        Err(_) => HashMap::new(),
+
+    let output = if source_txt.trim().is_empty() {
+        // Matches the indentation used by `spinners.json`.
+        let mut output = Vec::new();
+        let mut serializer = serde_json::Serializer::with_formatter(&mut output, PrettyFormatter::with_indent(b"\t"));
+
+        json!({ name: frames }).serialize(&mut serializer)?;
+        output.push(b'\n');
+
+        String::from_utf8(output).expect("serde_json writes UTF-8")
+    } else {
+        let source: Value = serde_json::from_str(&source_txt)?;
+
+        if !source.is_object() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "expected a JSON object"));
+        }
+
+        splice_spinner(&source_txt, name, frames)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "couldn't locate the spinner in the file"))?
     };
 
-    match source {
-        Some(path) => {
-            let source_txt = match fs::read_to_string(path) {
-                Ok(v) => v,
-                Err(_) => "{}".to_owned()
-            };
-            let source: SpinnJSON = match serde_json::from_str(&source_txt) {
-                Ok(e) => e,
-                Err(_) => HashMap::new(),
-            };
+    // Written next to the original first, so a failed write leaves it intact.
+    let path = Path::new(path);
+    let file_name = path.file_name().map(|v| v.to_string_lossy()).unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.tmp", file_name));
 
-            default.extend(source);
-            return default;
+    fs::write(&temp, output)?;
+    fs::rename(&temp, path).inspect_err(|_| { let _ = fs::remove_file(&temp); })
+}
+
+//|fS "chunk: Splicing"
+/// Replaces the frames of `name` in the JSON `text`, adding the spinner at
+/// the end when it's missing. Returns `None` for malformed JSON.
+fn splice_spinner (text: &str, name: &str, frames: &[String]) -> Option<String> {
+    let start = skip_ws(text, 0);
+    let (entries, close) = object_entries(text, start)?;
+
+    let mut output = text.to_owned();
+
+    match entries.iter().find(|(key, _, _)| key == name) {
+        Some((_, _, value)) if text.as_bytes()[value.start] == b'{' => {
+            // Details of object entries are kept.
+            let (fields, _) = object_entries(text, value.start)?;
+
+            match fields.iter().find(|(key, _, _)| key == "frames") {
+                Some((_, _, frames_at)) => {
+                    let array = format_frames(text, frames_at.clone(), frames);
+                    output.replace_range(frames_at.clone(), &array);
+                },
+                None => {
+                    let array = format_frames(text, value.clone(), frames);
+                    let field = match fields.first() {
+                        Some((_, key_at, _)) => format!("\"frames\": {},\n{}", array, line_indent(text, *key_at)),
+                        None => format!("{{ \"frames\": {} }}", array),
+                    };
+                    let at = fields.first().map_or(value.clone(), |(_, key_at, _)| *key_at..*key_at);
+
+                    output.replace_range(at, &field);
+                },
+            }
+        },
+        Some((_, _, value)) => {
+            let array = format_frames(text, value.clone(), frames);
+            output.replace_range(value.clone(), &array);
         },
         None => {
-            return default;
+            let key = serde_json::to_string(name).ok()?;
+
+            match entries.last() {
+                Some((_, key_at, value)) => {
+                    let indent = line_indent(text, *key_at);
+                    let array = format_frames(text, value.clone(), frames);
+
+                    output.insert_str(value.end, &format!(",\n{}{}: {}", indent, key, array));
+                },
+                None => {
+                    let array = format_frames(text, start..close + 1, frames);
+                    output.replace_range(start..close + 1, &format!("{{\n\t{}: {}\n}}", key, array));
+                },
+            }
+        },
+    }
+
+    Some(output)
+}
+
+/// Formats `frames` like the value at `like`, one frame per line when it
+/// spans several lines.
+fn format_frames (text: &str, like: Range<usize>, frames: &[String]) -> String {
+    let items: Vec<String> = frames.iter().map(|v| serde_json::to_string(v).unwrap_or_default()).collect();
+
+    if !text[like.clone()].contains('\n') {
+        return format!("[{}]", items.join(", "));
+    }
+
+    let indent = line_indent(text, like.start);
+    let step = indent_step(text, like.clone()).unwrap_or("\t");
+    let lines: Vec<String> = items.iter().map(|v| format!("{}{}{}", indent, step, v)).collect();
+
+    format!("[\n{}\n{}]", lines.join(",\n"), indent)
+}
+
+/// Indentation of the line holding `at`.
+fn line_indent (text: &str, at: usize) -> &str {
+    let line = text[..at].rfind('\n').map_or(0, |v| v + 1);
+    let rest = &text[line..];
+
+    &rest[..rest.len() - rest.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Extra indentation of the first nested line in `span`.
+fn indent_step (text: &str, span: Range<usize>) -> Option<&str> {
+    let outer = line_indent(text, span.start);
+    let line = text[span.clone()].find('\n')? + span.start + 1;
+    let inner = line_indent(text, line);
+
+    inner.strip_prefix(outer).filter(|v| !v.is_empty())
+}
+
+/// Key of an object entry, the position of the key & the span of the value.
+type Entry = (String, usize, Range<usize>);
+
+/// Entries of the JSON object starting at `start`, with the position of the
+/// closing brace.
+fn object_entries (text: &str, start: usize) -> Option<(Vec<Entry>, usize)> {
+    let bytes = text.as_bytes();
+    let mut entries = Vec::new();
+
+    if bytes.get(start) != Some(&b'{') {
+        return None;
+    }
+
+    let mut i = start + 1;
+
+    loop {
+        i = skip_ws(text, i);
+
+        match bytes.get(i)? {
+            b'}' => return Some((entries, i)),
+            b',' => i += 1,
+            b'"' => {
+                let key_end = value_end(text, i)?;
+                let key: String = serde_json::from_str(&text[i..key_end]).ok()?;
+                let colon = skip_ws(text, key_end);
+
+                if bytes.get(colon) != Some(&b':') {
+                    return None;
+                }
+
+                let value = skip_ws(text, colon + 1);
+                let end = value_end(text, value)?;
+
+                entries.push((key, i, value..end));
+                i = end;
+            },
+            _ => return None,
+        }
+    }
+}
+
+/// Position right after the JSON value starting at `start`.
+fn value_end (text: &str, start: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+
+    // Numbers, `true`, `false` & `null`.
+    if !matches!(bytes.get(start)?, b'"' | b'{' | b'[') {
+        let len = text[start..].find([',', '}', ']', ' ', '\t', '\r', '\n']).unwrap_or(text.len() - start);
+        return Some(start + len);
+    }
+
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut i = start;
+
+    while let Some(&byte) = bytes.get(i) {
+        i += 1;
+
+        if in_string {
+            match byte {
+                b'\\' => i += 1,
+                b'"' => {
+                    in_string = false;
+                    if depth == 0 { return Some(i); }
+                },
+                _ => {},
+            }
+            continue;
         }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 { return Some(i); }
+            },
+            _ => {},
+        }
+    }
+
+    None
+}
+
+fn skip_ws (text: &str, start: usize) -> usize {
+    start + text[start..].len() - text[start..].trim_start_matches([' ', '\t', '\r', '\n']).len()
+}
+//|fE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames (items: &[&str]) -> Vec<String> {
+        items.iter().map(|v| v.to_string()).collect()
+    }
+
+    /// Empty directory for the test `name` in the temp directory.
+    fn temp_dir (name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spinn-rs-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Writes `text` to a fresh file in the temp directory.
+    fn temp_file (name: &str, text: &str) -> PathBuf {
+        let path = temp_dir(name).join(name);
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    /// Catalog of the built-in spinners & two files both named `x.json`.
+    fn catalog (test: &str, policy: Conflict) -> Catalog {
+        let dir = temp_dir(test);
+        let mut catalog = Catalog::with_builtin(policy);
+
+        for (sub, text) in [ ("a", r#"{ "default": ["a"], "zz": ["1"] }"#), ("b", r#"{ "zz": ["2"] }"#) ] {
+            fs::create_dir_all(dir.join(sub)).unwrap();
+            fs::write(dir.join(sub).join("x.json"), text).unwrap();
+            catalog.read_source(&dir.join(sub).join("x.json"));
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+        catalog
+    }
+
+    /// Frames of the spinner `name` resolves to.
+    fn resolved (catalog: &mut Catalog, name: &str) -> Option<Vec<String>> {
+        let name = catalog.resolve(name)?;
+        catalog.items.get(&name).cloned()
+    }
+
+    #[test]
+    fn splice_keeps_other_entries () {
+        let text = "{\n  \"a\": [\"x\", \"y\"],\n  \"b\": [\n    \"1\",\n    \"2\"\n  ],\n  \"c\": {\"frames\": [\"-\"], \"interval\": 80}\n}\n";
+
+        // Inline arrays stay inline, nested ones keep the file's indent.
+        let output = splice_spinner(text, "b", &frames(&["3", "4", "5"])).unwrap();
+        assert_eq!(output, text.replace("[\n    \"1\",\n    \"2\"\n  ]", "[\n    \"3\",\n    \"4\",\n    \"5\"\n  ]"));
+
+        let output = splice_spinner(text, "a", &frames(&["\"q\""])).unwrap();
+        assert_eq!(output, text.replace("[\"x\", \"y\"]", "[\"\\\"q\\\"\"]"));
+
+        // Details of object entries are kept.
+        let output = splice_spinner(text, "c", &frames(&["+", "-"])).unwrap();
+        assert_eq!(output, text.replace("[\"-\"]", "[\"+\", \"-\"]"));
+
+        // New spinners go last, in the indent of the others.
+        let output = splice_spinner(text, "d", &frames(&["."])).unwrap();
+        assert_eq!(output, text.replace("80}\n}", "80},\n  \"d\": [\".\"]\n}"));
+        assert!(serde_json::from_str::<Value>(&output).is_ok());
+    }
+
+    #[test]
+    fn save_spinner_writes_in_place () {
+        let text = "{\n\t\"a\": [\n\t\t\"x\"\n\t],\n\t\"b\": [\"y\"]\n}\n";
+        let path = temp_file("save.json", text);
+        let path_txt = path.to_str().unwrap();
+
+        save_spinner(path_txt, "a", &frames(&["z"])).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), text.replace("\"x\"", "\"z\""));
+
+        // No temp file is left behind.
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        // Missing files are created.
+        let new = path.with_file_name("new.json");
+        save_spinner(new.to_str().unwrap(), "a", &frames(&["x"])).unwrap();
+        assert_eq!(fs::read_to_string(&new).unwrap(), "{\n\t\"a\": [\n\t\t\"x\"\n\t]\n}\n");
+
+        // Anything but an object is refused & left alone.
+        fs::write(&path, "[1]").unwrap();
+        assert!(save_spinner(path_txt, "a", &frames(&["z"])).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[1]");
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn packs_of_overridden_spinners () {
+        let mut catalog = catalog("override", Conflict::Override);
+        let builtin = catalog.shadowed.get("builtin:default").map(|(frames, _)| frames.clone()).unwrap();
+
+        assert!(catalog.warnings.iter().any(|w| w.contains("are both pack `x`, the later one is pack `x-2`.")), "{:?}", catalog.warnings);
+        assert_eq!(catalog.conflicts.len(), 2, "{:?}", catalog.conflicts);
+
+        // The last file wins, the others are still there by pack.
+        assert_eq!(resolved(&mut catalog, "default"), Some(frames(&["a"])));
+        assert_eq!(resolved(&mut catalog, "zz"), Some(frames(&["2"])));
+        assert_eq!(resolved(&mut catalog, "x-2:zz"), Some(frames(&["2"])));
+        assert_eq!(resolved(&mut catalog, "x:zz"), Some(frames(&["1"])));
+        assert_eq!(resolved(&mut catalog, "builtin:default"), Some(builtin));
+        assert_eq!(catalog.resolve("x:default").as_deref(), Some("default"));
+        assert_eq!(catalog.resolve("y:zz"), None);
+    }
+
+    #[test]
+    fn packs_of_kept_spinners () {
+        let mut catalog = catalog("keep-first", Conflict::KeepFirst);
+        let builtin = catalog.items["default"].clone();
+
+        assert_ne!(builtin, frames(&["a"]));
+        assert_eq!(resolved(&mut catalog, "zz"), Some(frames(&["1"])));
+        assert_eq!(resolved(&mut catalog, "x:zz"), Some(frames(&["1"])));
+        assert_eq!(resolved(&mut catalog, "x-2:zz"), Some(frames(&["2"])));
+        assert_eq!(resolved(&mut catalog, "x:default"), Some(frames(&["a"])));
+        assert_eq!(resolved(&mut catalog, "builtin:default"), Some(builtin));
+    }
+
+    #[test]
+    fn packs_of_renamed_spinners () {
+        let mut catalog = catalog("rename", Conflict::Rename);
+        let builtin = catalog.items["default"].clone();
+
+        assert_eq!(resolved(&mut catalog, "default-2"), Some(frames(&["a"])));
+        assert_eq!(catalog.resolve("x:default").as_deref(), Some("default-2"));
+        assert_eq!(catalog.resolve("builtin:default").as_deref(), Some("default"));
+        assert_eq!(resolved(&mut catalog, "builtin:default"), Some(builtin));
+        assert_eq!(catalog.resolve("x-2:zz").as_deref(), Some("zz-2"));
+        assert_eq!(resolved(&mut catalog, "x:zz"), Some(frames(&["1"])));
     }
 }
@@ -0,0 +1,361 @@
+// Keymaps for the preview & the editor
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde_json::Value;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Preview,
+    Editor,
+    Compare,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Slower,
+    Faster,
+    Next,
+    Previous,
+    NextPage,
+    PreviousPage,
+    First,
+    Last,
+    Edit,
+    Compare,
+    Favorite,
+    FavoritesOnly,
+    Sort,
+    Search,
+    Pad,
+
+    FrameDown,
+    FrameUp,
+    FramePageDown,
+    FramePageUp,
+    FrameFirst,
+    FrameLast,
+    MoveDown,
+    MoveUp,
+    EditFrame,
+    InsertAfter,
+    InsertBefore,
+    Duplicate,
+    Delete,
+    Undo,
+    Redo,
+    Save,
+
+    FocusNext,
+    FocusPrevious,
+    AddPanel,
+    RemovePanel,
+    Sync,
+    ToggleShared,
+}
+
+/// A key with it's modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+/// Entry in the keymap table.
+pub struct Binding {
+    pub scope: Scope,
+    pub action: Action,
+    /// Name used in the config file.
+    pub name: &'static str,
+    /// Text shown in the footer.
+    pub label: &'static str,
+    /// Text shown in the help message.
+    pub description: &'static str,
+    /// Whether to show this in the footer.
+    pub footer: bool,
+    pub defaults: &'static [&'static str],
+}
+
+const fn bind (
+    scope: Scope, action: Action, name: &'static str, label: &'static str, description: &'static str,
+    footer: bool, defaults: &'static [&'static str]
+) -> Binding {
+    Binding { scope, action, name, label, description, footer, defaults }
+}
+
+/// Default keymaps, in the order they are shown.
+pub const BINDINGS: &[Binding] = &[
+    bind(Scope::Preview, Action::Slower, "slower", "Delay+", "Increase the interval.", true, &["l", "Right"]),
+    bind(Scope::Preview, Action::Faster, "faster", "Delay-", "Decrease the interval.", true, &["h", "Left"]),
+    bind(Scope::Preview, Action::Next, "next", "Next", "Go to the next spinner.", true, &["j", "Down"]),
+    bind(Scope::Preview, Action::Previous, "previous", "Previous", "Go to the previous spinner.", true, &["k", "Up"]),
+    bind(Scope::Preview, Action::NextPage, "next_page", "Next page", "Skip 10 spinners forward.", false, &["PageDown"]),
+    bind(Scope::Preview, Action::PreviousPage, "previous_page", "Previous page", "Skip 10 spinners backward.", false, &["PageUp"]),
+    bind(Scope::Preview, Action::First, "first", "First", "Go to the first spinner.", false, &["Home"]),
+    bind(Scope::Preview, Action::Last, "last", "Last", "Go to the last spinner.", false, &["End"]),
+    bind(Scope::Preview, Action::Edit, "edit", "Edit", "Open the spinner in the editor.", true, &["e"]),
+    bind(Scope::Preview, Action::Compare, "compare", "Compare", "Compare spinners side by side.", false, &["c"]),
+    bind(Scope::Preview, Action::Favorite, "favorite", "Favorite", "Add or remove the spinner from the favorites.", false, &["f"]),
+    bind(Scope::Preview, Action::FavoritesOnly, "favorites_only", "Favorites", "Only show the favorite spinners.", false, &["F"]),
+    bind(Scope::Preview, Action::Sort, "sort", "Sort", "Cycle through the sort modes.", false, &["s"]),
+    bind(Scope::Preview, Action::Search, "search", "Search", "Search the spinners, Enter or Esc closes the prompt.", false, &["/"]),
+    bind(Scope::Preview, Action::Pad, "pad", "Pad", "Toggle padding the frames to the same width.", false, &["p"]),
+    bind(Scope::Preview, Action::Quit, "quit", "Quit", "Quit.", true, &["q", "Esc", "^d"]),
+
+    bind(Scope::Editor, Action::EditFrame, "edit_frame", "Edit", "Edit the selected frame.", true, &["i", "Enter"]),
+    bind(Scope::Editor, Action::InsertAfter, "insert_after", "Insert", "Insert a frame after the selected one.", true, &["o"]),
+    bind(Scope::Editor, Action::InsertBefore, "insert_before", "Insert before", "Insert a frame before the selected one.", false, &["O"]),
+    bind(Scope::Editor, Action::Delete, "delete", "Delete", "Delete the selected frame.", true, &["d"]),
+    bind(Scope::Editor, Action::Duplicate, "duplicate", "Duplicate", "Duplicate the selected frame.", true, &["y"]),
+    bind(Scope::Editor, Action::MoveDown, "move_down", "Move down", "Move the selected frame down.", true, &["J"]),
+    bind(Scope::Editor, Action::MoveUp, "move_up", "Move up", "Move the selected frame up.", true, &["K"]),
+    bind(Scope::Editor, Action::Undo, "undo", "Undo", "Undo the last change.", true, &["u"]),
+    bind(Scope::Editor, Action::Redo, "redo", "Redo", "Redo the last undone change.", true, &["^r"]),
+    bind(Scope::Editor, Action::Save, "save", "Save", "Save the spinner to its file(--source for built-in ones).", true, &["w"]),
+    bind(Scope::Editor, Action::FrameDown, "frame_down", "Down", "Select the next frame.", false, &["j", "Down"]),
+    bind(Scope::Editor, Action::FrameUp, "frame_up", "Up", "Select the previous frame.", false, &["k", "Up"]),
+    bind(Scope::Editor, Action::FramePageDown, "frame_page_down", "Page down", "Skip a page of frames forward.", false, &["PageDown"]),
+    bind(Scope::Editor, Action::FramePageUp, "frame_page_up", "Page up", "Skip a page of frames backward.", false, &["PageUp"]),
+    bind(Scope::Editor, Action::FrameFirst, "frame_first", "First", "Select the first frame.", false, &["Home"]),
+    bind(Scope::Editor, Action::FrameLast, "frame_last", "Last", "Select the last frame.", false, &["End"]),
+    bind(Scope::Editor, Action::Slower, "slower", "Delay+", "Increase the interval.", false, &["l", "Right"]),
+    bind(Scope::Editor, Action::Faster, "faster", "Delay-", "Decrease the interval.", false, &["h", "Left"]),
+    bind(Scope::Editor, Action::Quit, "quit", "Back", "Go back.", true, &["q", "Esc", "^d"]),
+
+    bind(Scope::Compare, Action::Slower, "slower", "Delay+", "Increase the interval.", false, &["l", "Right"]),
+    bind(Scope::Compare, Action::Faster, "faster", "Delay-", "Decrease the interval.", false, &["h", "Left"]),
+    bind(Scope::Compare, Action::Next, "next", "Next", "Show the next spinner in the focused panel.", false, &["j", "Down"]),
+    bind(Scope::Compare, Action::Previous, "previous", "Previous", "Show the previous spinner in the focused panel.", false, &["k", "Up"]),
+    bind(Scope::Compare, Action::FocusNext, "focus_next", "Focus", "Focus the next panel.", true, &["Tab"]),
+    bind(Scope::Compare, Action::FocusPrevious, "focus_previous", "Focus previous", "Focus the previous panel.", false, &["BackTab"]),
+    bind(Scope::Compare, Action::AddPanel, "add_panel", "Add", "Add a panel.", true, &["a"]),
+    bind(Scope::Compare, Action::RemovePanel, "remove_panel", "Remove", "Remove the focused panel.", true, &["x"]),
+    bind(Scope::Compare, Action::Sync, "sync", "Sync", "Toggle restarting every panel together at the end of the longest loop.", true, &["s"]),
+    bind(Scope::Compare, Action::ToggleShared, "toggle_shared", "Shared", "Toggle between a shared & independent intervals.", true, &["i"]),
+    bind(Scope::Compare, Action::Quit, "quit", "Back", "Go back.", true, &["q", "Esc", "^d"]),
+];
+
+impl Key {
+    /// Parses keys such as `j`, `J`, `^d`, `Ctrl-d`, `Alt-x`, `PageDown`.
+    pub fn parse (text: &str) -> Option<Key> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+
+        loop {
+            if let Some(r) = rest.strip_prefix('^') && !r.is_empty() {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = r;
+                continue;
+            }
+
+            let Some((prefix, r)) = rest.split_once('-') else {
+                break;
+            };
+
+            if r.is_empty() {
+                break;
+            }
+
+            match prefix.to_lowercase().as_str() {
+                "c" | "ctrl" => modifiers |= KeyModifiers::CONTROL,
+                "a" | "alt" => modifiers |= KeyModifiers::ALT,
+                "s" | "shift" => modifiers |= KeyModifiers::SHIFT,
+                _ => return None,
+            }
+
+            rest = r;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                f if f.starts_with('f') => KeyCode::F(f[1..].parse().ok()?),
+                _ => return None,
+            }
+        };
+
+        Some(Key { code, modifiers })
+    }
+
+    pub fn matches (&self, key: &KeyEvent) -> bool {
+        // Shift is already part of the character.
+        let ignored = match key.code {
+            KeyCode::Char(_) => KeyModifiers::SHIFT,
+            _ => KeyModifiers::NONE,
+        };
+
+        self.code == key.code && self.modifiers - ignored == key.modifiers - ignored
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt (&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "^")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    keys: Vec<(Scope, Action, Vec<Key>)>,
+}
+
+impl Keymap {
+    /// Creates the keymap from the `keymap` table of the config file.
+    ///
+    /// ```json
+    /// { "preview": { "next": [ "j", "Down" ] }, "editor": { "save": [ "^s" ] } }
+    /// ```
+    ///
+    /// Returns the keymap along with warnings about invalid entries.
+    pub fn from_config (config: Option<&Value>) -> (Keymap, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut keys = Vec::new();
+
+        for binding in BINDINGS {
+            let scope_name = match binding.scope {
+                Scope::Preview => "preview",
+                Scope::Editor => "editor",
+                Scope::Compare => "compare",
+            };
+
+            let defaults: Vec<Key> = binding.defaults.iter().filter_map(|k| Key::parse(k)).collect();
+            let user = config.and_then(|c| c.get(scope_name)).and_then(|s| s.get(binding.name));
+
+            let bound = match user {
+                Some(Value::Array(items)) => {
+                    let mut bound = Vec::new();
+
+                    for item in items {
+                        match item.as_str().and_then(Key::parse) {
+                            Some(key) => bound.push(key),
+                            None => warnings.push(format!("Invalid key {} for `{}.{}`.", item, scope_name, binding.name)),
+                        }
+                    }
+
+                    // An empty list unbinds the action, but a list of
+                    // invalid keys shouldn't(e.g. so that `quit` still works).
+                    if bound.is_empty() && !items.is_empty() {
+                        defaults
+                    } else {
+                        bound
+                    }
+                },
+                Some(Value::String(item)) => match Key::parse(item) {
+                    Some(key) => vec![key],
+                    None => {
+                        warnings.push(format!("Invalid key \"{}\" for `{}.{}`.", item, scope_name, binding.name));
+                        defaults
+                    }
+                },
+                Some(_) => {
+                    warnings.push(format!("Expected a key or a list of keys for `{}.{}`.", scope_name, binding.name));
+                    defaults
+                },
+                None => defaults,
+            };
+
+            keys.push((binding.scope, binding.action, bound));
+        }
+
+        if let Some(Value::Object(scopes)) = config {
+            for (scope_name, actions) in scopes {
+                let scope = match scope_name.as_str() {
+                    "preview" => Scope::Preview,
+                    "editor" => Scope::Editor,
+                    "compare" => Scope::Compare,
+                    _ => {
+                        warnings.push(format!("Unknown keymap scope `{}`.", scope_name));
+                        continue;
+                    }
+                };
+
+                let Some(actions) = actions.as_object() else {
+                    continue;
+                };
+
+                for name in actions.keys() {
+                    if !BINDINGS.iter().any(|b| b.scope == scope && b.name == name) {
+                        warnings.push(format!("Unknown action `{}.{}`.", scope_name, name));
+                    }
+                }
+            }
+        }
+
+        (Keymap { keys }, warnings)
+    }
+
+    /// Gets the action bound to `key`.
+    pub fn action (&self, scope: Scope, key: &KeyEvent) -> Option<Action> {
+        self.keys.iter()
+            .find(|(s, _, keys)| *s == scope && keys.iter().any(|k| k.matches(key)))
+            .map(|(_, action, _)| *action)
+    }
+
+    /// Keys bound to `action`.
+    pub fn keys (&self, scope: Scope, action: Action) -> &[Key] {
+        self.keys.iter()
+            .find(|(s, a, _)| *s == scope && *a == action)
+            .map(|(_, _, keys)| keys.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Key & label pairs shown in the footer.
+    pub fn footer (&self, scope: Scope) -> Vec<(String, &'static str)> {
+        let mut items = Vec::new();
+
+        for binding in BINDINGS.iter().filter(|b| b.scope == scope && b.footer) {
+            if let Some(key) = self.keys(scope, binding.action).first() {
+                items.push((key.to_string(), binding.label));
+            }
+        }
+
+        items
+    }
+
+    /// Keys & descriptions shown in the help message.
+    pub fn help (&self, scope: Scope) -> Vec<(String, &'static str)> {
+        let mut items = Vec::new();
+
+        for binding in BINDINGS.iter().filter(|b| b.scope == scope) {
+            let keys: Vec<String> = self.keys(scope, binding.action).iter().map(|k| k.to_string()).collect();
+
+            if !keys.is_empty() {
+                items.push((keys.join(", "), binding.description));
+            }
+        }
+
+        items
+    }
+}
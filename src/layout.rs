@@ -0,0 +1,49 @@
+// Helpers for fitting the UI in the terminal
+use crossterm::terminal;
+
+use crate::width;
+
+/// Width below which the compact layout is used.
+pub const COMPACT_WIDTH: u16 = 40;
+
+pub fn terminal_width () -> u16 {
+    terminal_size().0
+}
+
+/// Width & height of the terminal.
+pub fn terminal_size () -> (u16, u16) {
+    terminal::size().unwrap_or((80, 24))
+}
+
+/// Cuts `text` down to `width` cells, the end is replaced with `…` when
+/// the text is cut.
+pub fn truncate (text: &str, width: usize) -> String {
+    width::truncate(text, width)
+}
+
+/// Number of `items` that fit in `width` when joined with `separator`.
+pub fn fitting (items: &[String], separator: &str, width: usize) -> usize {
+    let mut used = 0;
+
+    for (i, item) in items.iter().enumerate() {
+        let size = width::width(item) + if i > 0 { width::width(separator) } else { 0 };
+
+        if used + size > width {
+            return i;
+        }
+
+        used += size;
+    }
+
+    items.len()
+}
+
+/// Padding needed to center text of `size` in `width`.
+pub fn center_pad (size: usize, width: usize) -> usize {
+    width.saturating_sub(size) / 2
+}
+
+/// Cuts or pads `text` so that it takes exactly `width` cells.
+pub fn fit (text: &str, width: usize) -> String {
+    width::pad(&truncate(text, width), width)
+}
@@ -1,15 +1,38 @@
 use crossterm::{
-    cursor::{self, Hide, MoveDown, MoveToColumn, MoveUp, Show}, 
-    event::{self, Event, KeyCode, KeyModifiers},
+    cursor::{self, Hide, MoveUp, Show}, 
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, MouseButton, MouseEvent, MouseEventKind},
     execute,
     terminal::{self, Clear, ClearType}
 };
-use std::{collections::HashMap, io::stdout, time::Duration};
+use std::{borrow::Cow, collections::HashMap, io::{self, stdout, Write}, path::Path};
 
 mod term;
 mod args;
 mod json;
 mod export;
+mod editor;
+mod config;
+mod keymap;
+mod theme;
+mod layout;
+mod compare;
+mod state;
+mod fullscreen;
+mod render;
+mod ticker;
+mod width;
+mod suggest;
+mod commands;
+mod completions;
+mod search;
+
+use keymap::Action;
+use render::Renderer;
+
+/// Start of the preview's header, before the spinner's name.
+const HEADER_START: &str = " 󰢵 Style: ";
+/// End of the preview's header, after the spinner's name.
+const HEADER_END: &str = " 󰓎 󰸽 󰹁 ";
 
 /// Spinn CLI
 struct Spinn {
@@ -19,301 +42,208 @@ struct Spinn {
     export: Option<export::SpinnExport>,
     multi_line: Option<bool>,
     quote: char,
+    source: Option<String>,
+    /// Row where the preview starts, used for mouse events.
+    top: u16,
+    keymap: keymap::Keymap,
+    theme: theme::Theme,
+    /// Spinners shown in the comparison view.
+    compare: Vec<String>,
+    state: state::State,
+    /// Whether only the favorites are shown.
+    favorites_only: bool,
+    terminal: render::Terminal,
+
+    items: json::SpinnJSON,
+    sort: args::Sort,
+    /// Names of the shown spinners in the `sort` order, updated by
+    /// `sort_keys()`.
+    keys: Vec<String>,
+    /// Query of the search prompt, if it's open.
+    search: Option<String>,
+    /// Whether the frames are padded to the same width.
+    padded: bool,
+    align: width::Align,
+    fill: char,
+    /// Details of the spinners from the catalog.
+    meta: HashMap<String, json::Meta>,
+}
+
+/// Styles the markup used by the argument descriptions, `code` is a key,
+/// `[section]` a link & `{value}` a value.
+fn highlight (t: &theme::Theme, text: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(['`', '[', '{']) {
+        let close = match &rest[start..start + 1] {
+            "`" => '`',
+            "[" => ']',
+            _ => '}',
+        };
+        let Some(end) = rest[start + 1..].find(close).map(|e| start + 1 + e) else {
+            break;
+        };
+        let inner = &rest[start + 1..end];
 
-    items: HashMap<String, Vec<String>>,
+        output += &t.text.paint(&rest[..start]).to_string();
+        output += &match close {
+            '`' => t.key.paint(inner),
+            ']' => t.accent.underline().paint(inner),
+            _ => t.value.paint(inner),
+        }.to_string();
+
+        rest = &rest[end + 1..];
+    }
+
+    output + &t.text.paint(rest).to_string()
 }
 
 impl Spinn {
-    fn help (&self) {
-        println!("");
-        println!(
+    /// Prints the help. Like `list`, it's text that scrolls & can be piped
+    /// rather than a fixed area that's redrawn, so it doesn't go through a
+    /// `Renderer`(which would cut the lines at the terminal's width).
+    fn help (&self) -> io::Result<()> {
+        let t = &self.theme;
+        let mut out = stdout().lock();
+
+        writeln!(out)?;
+        writeln!(
+            out,
             "{} {}",
-            term::color(35) + env!("CARGO_PKG_NAME"),
-            term::bold() + &term::color(33) + "v" + env!("CARGO_PKG_VERSION") + &term::reset(),
-        );
-        println!(
-            "{}Spinners for the terminal!{}",
-            term::bold() + &term::color(2),
-            term::reset(),
-        );
+            t.title.paint(env!("CARGO_PKG_NAME")),
+            t.key.bold().paint(concat!("v", env!("CARGO_PKG_VERSION"))),
+        )?;
+        writeln!(out, "{}", t.muted.bold().paint("Spinners for the terminal!"))?;
+
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{} {} {} {} {}",
+            t.accent.underline().paint("Usage:"),
+
+            t.title.bold().paint("spinn-rs"),
+            t.muted.paint("[command]"),
+            t.key.paint("<spinner_name>"),
+            t.value.paint("<args>"),
+        )?;
+
+        let arg_col_size: usize = 20;
+
+        writeln!(out)?;
+        writeln!(out, "{}", t.accent.underline().paint("Commands:"))?;
+        writeln!(out)?;
+
+        for command in args::COMMANDS.iter().filter(|c| !c.name.starts_with("__")) {
+            let operand = command.operand.unwrap_or_default();
+            let pad = " ".repeat(arg_col_size.saturating_sub(command.name.len() + 1 + operand.len()));
+
+            writeln!(out, "  {} {}{}    {}", t.key.paint(command.name), t.value.paint(operand), pad, highlight(t, command.help))?;
+        }
 
-        println!("");
-        println!(
-            "{}Usage:{} {}spinn-rs {}<spinner_name> {}<args>",
-            term::underlined() + &term::color(32),
-            term::reset(),
+        writeln!(out)?;
+        writeln!(out, "{}", t.accent.underline().paint("Arguments:"))?;
+        writeln!(out)?;
 
-            term::bold() + &term::color(34),
-            term::reset() + &term::color(33),
-            term::reset() + &term::color(31),
-        );
+        for arg in args::ARGS {
+            let (name, value) = arg.usage(false);
+            let pad = " ".repeat(arg_col_size.saturating_sub(name.len() + value.len()));
 
-        println!("");
-        println!(
-            "{}Arguments:{}",
-            term::underlined() + &term::color(32),
-            term::reset(),
-        );
-        println!("");
-
-        let arg_col_size = 20;
-        let mut args = Vec::new();
-
-        args.push(
-            (
-                format!(
-                    "{}--export={}<as>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 13),
-                ),
-                format!(
-                    "Export format. See {}Expprt options{}.",
-                    term::underlined() + &term::color(32),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}--help{}",
-                    term::color(33),
-                    term::reset() + &" ".repeat(arg_col_size - 6),
-                ),
-                format!(
-                    "Shows {}this message{}.",
-                    term::underlined() + &term::color(32),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}--interval={}<ms>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 15),
-                ),
-                format!(
-                    "Interval between each frame in {}miliseconds{}.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}--multiline={}<bool>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 18),
-                ),
-                format!(
-                    "Whether to export the output in {}multiple lines{}.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}--quote={}<char>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 14),
-                ),
-                format!(
-                    "Text to use for {}quoting{} strings when exporting.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}--source={}<path>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 15),
-                ),
-                format!(
-                    "Path to a {}JSON{} file containing spinners.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push((String::new(), String::new()));
-        args.push(
-            (
-                format!(
-                    "{}-e={}<as>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 7),
-                ),
-                format!(
-                    "Alias for {}--export{}.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}-h{}",
-                    term::color(33),
-                    term::reset() + &" ".repeat(arg_col_size - 2),
-                ),
-                format!(
-                    "Alias for {}--help{}.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}-i={}<ms>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 7),
-                ),
-                format!(
-                    "Alias for {}--interval{}.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}-m={}<bool>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 9),
-                ),
-                format!(
-                    "Alias for {}--multiline{}.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}-q={}<char>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 9),
-                ),
-                format!(
-                    "Alias for {}--quote{}.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
-        args.push(
-            (
-                format!(
-                    "{}-s={}<path>{}",
-                    term::color(33),
-                    term::color(36),
-                    term::reset() + &" ".repeat(arg_col_size - 9),
-                ),
-                format!(
-                    "Alias for {}--source{}.",
-                    term::color(33),
-                    term::reset() + &term::color(97),
-                )
-            )
-        );
+            writeln!(out, "  {}{}{}    {}", t.key.paint(name), t.value.paint(value), pad, highlight(t, arg.help))?;
+        }
+
+        writeln!(out)?;
+
+        let mut aliases: Vec<&args::Arg> = args::ARGS.iter().filter(|a| a.short.is_some()).collect();
+        aliases.sort_by_key(|a| a.short.map(|c| (c.to_ascii_lowercase(), c.is_ascii_lowercase())));
+
+        for arg in aliases {
+            let (name, value) = arg.usage(true);
+            let pad = " ".repeat(arg_col_size.saturating_sub(name.len() + value.len()));
 
-        for (k, v) in args {
-            println!("  {}    {}{}", k, term::color(97), v);
+            writeln!(out, "  {}{}{}    {}", t.key.paint(name), t.value.paint(value), pad, highlight(t, &format!("Alias for `--{}`.", arg.long)))?;
         }
 
-        println!("");
-        println!(
-            "{}Spiners:{}",
-            term::underlined() + &term::color(32),
-            term::reset(),
-        );
-        println!("");
+        writeln!(out)?;
+        writeln!(out, "{}", t.accent.underline().paint("Spiners:"))?;
+        writeln!(out)?;
 
-        for (name, frames) in &self.items {
+        for name in &self.keys {
+            let frames = &self.items[name];
             let max = frames.len() as f32;
 
             let _mid: f32 = max / 2.0;
             let mid = _mid.floor() as usize;
 
-            let as_text = format!("{}", frames[mid]);
-
-            println!(
-                "  {}{:<arg_col_size$}    {}{}",
+            // Spinners without frames show nothing.
+            let as_text = frames.get(mid).map(|f| term::adapt(f)).unwrap_or_default();
 
-                term::color(33),
-                name,
+            writeln!(
+                out,
+                "  {}    {}",
 
-                term::color(97),
-                as_text
-            );
+                t.key.paint(width::pad(name, arg_col_size)),
+                t.spinner.paint(as_text),
+            )?;
         }
 
-        println!("");
-        println!(
-            "{}Export options:{}",
-            term::underlined() + &term::color(32),
-            term::reset(),
-        );
-        println!("");
+        writeln!(out)?;
+        writeln!(out, "{}", t.accent.underline().paint("Export options:"))?;
+        writeln!(out)?;
 
-        let mut export_format = Vec::new();
-        export_format.push(
-            ( "array", "{ \"a\", \"b\", \"c\" }" )
-        );
-        export_format.push(
-            ( "list", "[ \"a\", \"b\", \"c\" ]" )
-        );
-        export_format.push(
-            ( "string", "a b c" )
-        );
-        export_format.push(( "", "" ));
-        export_format.push(
-            ( "a", "Alias for 'array'" )
-        );
-        export_format.push(
-            ( "l", "Alias for 'list'" )
-        );
-        export_format.push(
-            ( "s", "Alias for 'string'" )
-        );
+        let export_format = vec![
+            ( "array", "{ \"a\", \"b\", \"c\" }" ),
+            ( "list", "[ \"a\", \"b\", \"c\" ]" ),
+            ( "string", "a b c" ),
+            ( "", "" ),
+            ( "a", "Alias for 'array'" ),
+            ( "l", "Alias for 'list'" ),
+            ( "s", "Alias for 'string'" ),
+        ];
 
         for (k, v) in export_format {
-            println!("  {}{:<arg_col_size$}    {}{}", term::color(33), k, term::color(97), v);
+            writeln!(out, "  {}    {}", t.key.paint(width::pad(k, arg_col_size)), t.text.paint(v))?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "{}", t.accent.underline().paint("Themes:"))?;
+        writeln!(out)?;
+
+        for name in theme::THEMES {
+            writeln!(out, "  {}    {}", t.key.paint(width::pad(name, arg_col_size)), t.text.paint("Built-in theme."))?;
+        }
+
+        writeln!(
+            out,
+            "  {}    {}{}{}",
+            t.key.paint(width::pad("...", arg_col_size)),
+            t.text.paint("User themes from the "),
+            t.value.paint("themes"),
+            t.text.paint(" table of the config file."),
+        )?;
+
+        for (title, scope) in [ ("Preview keymaps:", keymap::Scope::Preview), ("Editor keymaps:", keymap::Scope::Editor), ("Compare keymaps:", keymap::Scope::Compare) ] {
+            writeln!(out)?;
+            writeln!(out, "{}", t.accent.underline().paint(title))?;
+            writeln!(out)?;
+
+            for (k, v) in self.keymap.help(scope) {
+                writeln!(out, "  {}    {}", t.key.paint(width::pad(&k, arg_col_size)), t.text.paint(v))?;
+            }
         }
 
+        Ok(())
     }
 
-    fn export (&self) {
+    fn export (&self) -> io::Result<()> {
         if self.export.is_none() {
-            return;
+            return Ok(());
         }
 
-        let _as = self.export.as_ref().unwrap_or_else(|| &export::SpinnExport::List);
-        let _ml = self.multi_line.as_ref().unwrap_or_else(|| &true);
+        let _as = self.export.as_ref().unwrap_or(&export::SpinnExport::List);
+        let _ml = self.multi_line.as_ref().unwrap_or(&true);
 
-        export::export(_as, &self.quote, _ml, &self.items[&self.pick]);
+        writeln!(stdout().lock(), "{}", export::render(_as, &self.quote, _ml, &self.frames(&self.pick)))
     }
 
     fn clear_output (&self) {
@@ -325,22 +255,49 @@ impl Spinn {
         ).ok();
     }
 
-    fn _layouts (&self) -> Vec<String> {
-        let mut keys: Vec<String> = vec![];
+    /// Frames of a spinner, padded to the same width when `padded` is set.
+    fn frames (&self, name: &str) -> Cow<'_, [String]> {
+        if self.padded {
+            Cow::Owned(width::normalize(&self.items[name], self.align, self.fill))
+        } else {
+            Cow::Borrowed(&self.items[name])
+        }
+    }
+
+    /// Updates `keys`, after the spinners, the sort mode or the favorites
+    /// changed.
+    fn sort_keys (&mut self) {
+        let mut keys: Vec<String> = self.items.keys()
+            .filter(|key| !self.favorites_only || self.state.is_favorite(key))
+            .cloned()
+            .collect();
+
+        // Sorts are stable, so ties keep the order of the files.
+        match self.sort {
+            args::Sort::File => {},
+            args::Sort::Name => keys.sort(),
+            args::Sort::Frames => keys.sort_by_key(|key| self.items[key].len()),
+            args::Sort::Width => keys.sort_by_key(|key| self.items[key].iter().map(|f| width::width(f)).max().unwrap_or(0)),
+            args::Sort::Recent => keys.sort_by_key(|key| self.state.recent.iter().position(|r| r == key).unwrap_or(usize::MAX)),
+        }
+
+        // Searching only shows the matches, unless there are none.
+        if let Some(query) = &self.search {
+            let matching: Vec<String> = keys.iter().filter(|key| suggest::score(query, key).is_some()).cloned().collect();
 
-        for key in self.items.keys() {
-            keys.push(key.into());
+            if !matching.is_empty() {
+                keys = matching;
+            }
         }
 
-        keys.sort();
-        keys
+        self.keys = keys;
     }
 
     fn next_loader (&mut self) {
-        let keys: Vec<String> = self._layouts();
+        let keys = &self.keys;
         let current_index = keys.iter().position(|s| s == &self.pick).expect("");
 
-        let _c_index = (current_index + 1) as usize;
+        let _c_index = current_index + 1;
 
         if _c_index < keys.len() {
             self.pick = keys[current_index + 1].to_owned();
@@ -350,10 +307,10 @@ impl Spinn {
     }
 
     fn prev_loader (&mut self) {
-        let keys: Vec<String> = self._layouts();
+        let keys = &self.keys;
         let current_index = keys.iter().position(|s| s == &self.pick).expect("");
 
-        let _c_index = (current_index + 1) as usize;
+        let _c_index = current_index + 1;
 
         if _c_index > 1 {
             self.pick = keys[current_index - 1].to_owned();
@@ -362,41 +319,161 @@ impl Spinn {
         }
     }
 
+    /// Moves `offset` spinners forward(or backward). Unlike `next_loader()`
+    /// & `prev_loader()` this stops at the first & last spinner.
+    fn skip_loader (&mut self, offset: isize) {
+        let keys = &self.keys;
+        let current_index = keys.iter().position(|s| s == &self.pick).expect("") as isize;
+
+        let index = (current_index + offset).clamp(0, keys.len() as isize - 1);
+        self.pick = keys[index as usize].to_owned();
+    }
+
+    fn toggle_favorite (&mut self) {
+        self.state.toggle_favorite(&self.pick);
+
+        // The spinner is hidden now, so go to the one that took it's place.
+        if self.favorites_only && !self.state.is_favorite(&self.pick) {
+            let index = self.keys.iter().position(|s| s == &self.pick).unwrap_or(0);
+            self.sort_keys();
+
+            if self.keys.is_empty() {
+                self.favorites_only = false;
+                self.sort_keys();
+            } else {
+                self.pick = self.keys[index.min(self.keys.len() - 1)].to_owned();
+                self.frame = 0_usize;
+            }
+        }
+
+        self.state.write().ok();
+    }
+
+    /// Toggles between showing every spinner & only the favorites. Does
+    /// nothing if there are no favorites.
+    fn toggle_favorites_only (&mut self) {
+        if !self.favorites_only && !self.items.keys().any(|k| self.state.is_favorite(k)) {
+            return;
+        }
+
+        self.favorites_only = !self.favorites_only;
+        self.sort_keys();
+
+        if !self.keys.contains(&self.pick) {
+            self.pick = self.keys[0].to_owned();
+            self.frame = 0_usize;
+        }
+    }
+
+    /// Name of the padding shown in the previews.
+    fn pad_name (&self) -> &'static str {
+        match (self.padded, self.align) {
+            (false, _) => "off",
+            (true, width::Align::Left) => "left",
+            (true, width::Align::Right) => "right",
+            (true, width::Align::Center) => "center",
+        }
+    }
+
+    /// Switches to the next sort mode, the current spinner stays selected.
+    fn cycle_sort (&mut self) {
+        self.sort = self.sort.next();
+        self.sort_keys();
+    }
+
+    /// Applies an action shared by the inline & fullscreen previews.
+    /// Returns `false` when the action isn't one of them.
+    fn preview_action (&mut self, action: Action) -> bool {
+        match action {
+            Action::Faster if self.interval > 100 => {
+                self.interval -= 50;
+            },
+            Action::Slower if self.interval < 1000 => {
+                self.interval += 50;
+            },
+            Action::Next => {
+                self.next_loader();
+                self.frame = 0_usize;
+            },
+            Action::Previous => {
+                self.prev_loader();
+                self.frame = 0_usize;
+            },
+            Action::NextPage => {
+                self.skip_loader(10);
+                self.frame = 0_usize;
+            },
+            Action::PreviousPage => {
+                self.skip_loader(-10);
+                self.frame = 0_usize;
+            },
+            Action::First => {
+                self.skip_loader(-(self.items.len() as isize));
+                self.frame = 0_usize;
+            },
+            Action::Last => {
+                self.skip_loader(self.items.len() as isize);
+                self.frame = 0_usize;
+            },
+            Action::Favorite => {
+                self.toggle_favorite();
+            },
+            Action::FavoritesOnly => {
+                self.toggle_favorites_only();
+            },
+            Action::Sort => {
+                self.cycle_sort();
+            },
+            Action::Search => {
+                self.start_search("");
+            },
+            Action::Pad => {
+                self.padded = !self.padded;
+            },
+            _ => return false
+        }
+
+        true
+    }
+
     fn _show_indicator (&self) -> (bool, bool) {
-        let keys: Vec<String> = self._layouts();
+        let keys = &self.keys;
 
         let mut at_start = false;
         let mut at_end = false;
 
-        let mut _current_index = 1;
-
-        for key in keys.iter() {
+        for (_current_index, key) in (1..).zip(keys.iter()) {
             if key == &self.pick {
                 if _current_index == 1 {
                     at_start = true;
-                } else if _current_index as usize == keys.len() {
+                } else if _current_index == keys.len() {
                     at_end = true;
                 }
 
                 break;
             }
-
-            _current_index += 1;
         }
 
         (at_start, at_end)
     }
 
-    fn next_frame (&mut self) {
-        let frames = &self.items[&self.pick];
+    /// Draws the current frame of the preview.
+    fn draw_preview (&self, r: &mut dyn Renderer) {
+        let t = &self.theme;
+        let frames = self.frames(&self.pick);
         let max = frames.len();
 
-        let current = &frames[self.frame];
-        let terminal_w: u16 = match terminal::size() {
-            Ok(w) => w.0,
-            Err(_) => 80
+        let current = frames.get(self.frame).map(|f| f.as_str()).unwrap_or_default();
+        let terminal_w: u16 = r.size().0 as u16;
+        let compact = terminal_w < layout::COMPACT_WIDTH;
+
+        // Header is `[ 󰢵 Style: ]<name> 󰓎 󰸽 󰹁 `.
+        let header_size = if compact {
+            1 + width::width(HEADER_END)
+        } else {
+            width::width(HEADER_START) + width::width(HEADER_END)
         };
-        let name_size = (terminal_w - (3 + 5 + 7)) as usize;
+        let name_size = (terminal_w as usize).saturating_sub(header_size);
         let (at_start, at_end) = self._show_indicator();
         let loader_size = terminal_w as usize;
 
@@ -404,176 +481,315 @@ impl Spinn {
         let frame_size = _frame_count.chars().count();
 
         let stat = format!(
-            "󰄉 Interval: {}ms • 󰕟 Current: {:0frame_size$} • 󰕬 Frames: {}",
+            "󰄉 Interval: {}ms • 󰕟 Current: {:0frame_size$} • 󰕬 Frames: {} • 󰒺 Sort: {} • 󰉶 Pad: {}",
 
             self.interval,
             self.frame + 1,
-            max
+            max,
+            self.sort.name(),
+            self.pad_name()
         );
-        let stat_len = stat.chars().count() as u16;
-        let _stat_pad = ((terminal_w - stat_len) / 2) as f32;
-        let stat_pad = _stat_pad.floor() as usize;
+        let short_stat = format!("󰄉 {}ms • 󰕟 {:0frame_size$}/{}", self.interval, self.frame + 1, max);
 
-        let keymap_len = "󰌏  l: Delay+, h: Delay-, j: Next, k; Previous, q: Quit".chars().count() as u16;
-        let _keymap_pad = ((terminal_w - keymap_len) / 2) as f32;
-        let keymap_pad = _keymap_pad.floor() as usize;
+        let stat_len = width::width(&stat);
+        let short_stat_len = width::width(&short_stat);
 
-        execute!(stdout(), MoveToColumn(0)).ok();
-        print!(
-            "{} 󰢵 {}Style: {}{:<name_size$}{} {}󰸽 {}󰹁 ",
+        let keymap = self.keymap.footer(keymap::Scope::Preview);
+        let keymap_text: Vec<String> = keymap.iter().map(|(key, label)| format!("{}: {}", key, label)).collect();
 
-            term::color(32),
-            term::color(97),
-            term::color(34) + &term::bold(),
+        // Only show the keymaps that fit.
+        let icon_size = width::width("󰌏  ");
+        let keymap_count = layout::fitting(&keymap_text, ", ", (terminal_w as usize).saturating_sub(icon_size + 2));
+        let keymap_len = icon_size + width::width(&keymap_text[..keymap_count].join(", ")) + if keymap_count < keymap.len() { 2 } else { 0 };
+        let keymap_pad = layout::center_pad(keymap_len, terminal_w as usize);
 
-            &self.pick,
+        let mut x = 1;
 
-            term::reset(),
-            if !at_end    { term::color(32) } else { term::color(97) },
-            if !at_start  { term::color(32) } else { term::color(97) },
-        );
-        execute!(stdout(), MoveDown(1), MoveToColumn(0)).ok();
-        print!("");
-        execute!(stdout(), MoveDown(1), MoveToColumn(0)).ok();
-        print!(
-            "{}{:^loader_size$}{}",
-
-            term::reset() + &term::color(97),
-            current,
-            term::reset(),
-        );
-        execute!(stdout(), MoveDown(1), MoveToColumn(0)).ok();
-        print!("");
-        execute!(stdout(), MoveDown(1), MoveToColumn(0)).ok();
-        print!(
-            "{:<stat_pad$}{}󰄉 {}Interval: {}ms{} • {}󰕟 {}Current: {}{:0frame_size$}{} • {}󰕬 {}Frames: {}",
+        if !compact {
+            // The icon changes when only favorites are shown.
+            x = r.print(x, 0, if self.favorites_only { "󰓎 " } else { "󰢵 " }, &t.accent);
+            x = r.print(x, 0, "Style: ", &t.text);
+        }
 
-            "",
+        // The pack is shown after the name when both fit.
+        let pack = self.meta.get(&self.pick).map(|m| format!(" ({})", m.pack)).unwrap_or_default();
 
-            term::color(34) + &term::bold(),
-            term::color(97),
+        if width::width(&self.pick) + width::width(&pack) <= name_size {
+            x = r.print(x, 0, &self.pick, &t.title.bold());
+            x = r.print(x, 0, &layout::fit(&pack, name_size - width::width(&self.pick)), &t.muted);
+        } else {
+            x = r.print(x, 0, &layout::fit(&self.pick, name_size), &t.title.bold());
+        }
+        x = r.print(x + 1, 0, if self.state.is_favorite(&self.pick) { "󰓎" } else { " " }, &t.key);
+        x = r.print(x + 1, 0, "󰸽", if !at_end { &t.accent } else { &t.text });
+        r.print(x + 1, 0, "󰹁", if !at_start { &t.accent } else { &t.text });
+
+        let current = layout::truncate(&term::adapt(current), loader_size);
+        r.print(width::center_pad(&current, loader_size), 2, &current, &t.spinner);
+
+        if stat_len <= terminal_w as usize && !compact {
+            let mut x = layout::center_pad(stat_len, terminal_w as usize);
+
+            x = r.print(x, 4, "󰄉 ", &t.title.bold());
+            x = r.print(x, 4, "Interval: ", &t.text);
+            x = r.print(x, 4, &format!("{}ms", self.interval), &t.value);
+            x = r.print(x, 4, " • ", &t.text);
+            x = r.print(x, 4, "󰕟 ", &t.accent.bold());
+            x = r.print(x, 4, "Current: ", &t.text);
+            x = r.print(x, 4, &format!("{:0frame_size$}", self.frame + 1), &t.value);
+            x = r.print(x, 4, " • ", &t.text);
+            x = r.print(x, 4, "󰕬 ", &t.accent.bold());
+            x = r.print(x, 4, "Frames: ", &t.text);
+            x = r.print(x, 4, &max.to_string(), &t.value);
+            x = r.print(x, 4, " • ", &t.text);
+            x = r.print(x, 4, "󰒺 ", &t.accent.bold());
+            x = r.print(x, 4, "Sort: ", &t.text);
+            x = r.print(x, 4, self.sort.name(), &t.value);
+            x = r.print(x, 4, " • ", &t.text);
+            x = r.print(x, 4, "󰉶 ", &t.accent.bold());
+            x = r.print(x, 4, "Pad: ", &t.text);
+            r.print(x, 4, self.pad_name(), &t.value);
+        } else if short_stat_len <= terminal_w as usize {
+            let mut x = layout::center_pad(short_stat_len, terminal_w as usize);
+
+            x = r.print(x, 4, "󰄉 ", &t.title.bold());
+            x = r.print(x, 4, &format!("{}ms", self.interval), &t.value);
+            x = r.print(x, 4, " • ", &t.text);
+            x = r.print(x, 4, "󰕟 ", &t.accent.bold());
+            x = r.print(x, 4, &format!("{:0frame_size$}", self.frame + 1), &t.value);
+            r.print(x, 4, &format!("/{}", max), &t.text);
+        } else {
+            r.print(0, 4, &layout::truncate(&short_stat, terminal_w as usize), &t.text);
+        }
 
-            term::color(36) + &self.interval.to_string() + &term::color(33),
-            term::color(97),
+        if self.search.is_some() {
+            self.draw_search(r, 1, 5);
+        } else if keymap_count > 0 {
+            let mut x = r.print(keymap_pad, 5, "󰌏  ", &t.title);
 
-            term::color(35) + &term::bold(),
-            term::color(97),
+            for (k, (key, label)) in keymap.iter().take(keymap_count).enumerate() {
+                x = r.print(x, 5, key, &t.value.bold());
+                x = r.print(x, 5, ": ", &t.text);
+                x = r.print(x, 5, label, &t.accent);
 
-            term::color(36),
-            self.frame + 1,
-            term::color(97),
+                if k + 1 < keymap_count {
+                    x = r.print(x, 5, ", ", &t.text);
+                }
+            }
 
-            term::color(32) + &term::bold(),
-            term::color(97),
+            if keymap_count < keymap.len() {
+                r.print(x, 5, " …", &t.muted);
+            }
+        }
+    }
 
-            term::color(37) + &max.to_string() + &term::reset(),
-        );
-        execute!(stdout(), MoveDown(1), MoveToColumn(0)).ok();
-        print!(
-            "{:<keymap_pad$}{}󰌏  {}l{}: {}Delay+, {}h{}: {}Delay-, {}j{}: {}Next, {}k{}; {}Previous, {}q{}: {}Quit",
+    /// Draws the current frame, only the parts that changed are redrawn.
+    fn render (&mut self) {
+        let mut terminal = std::mem::take(&mut self.terminal);
 
-            "",
-            term::color(34),
+        terminal.begin(layout::terminal_width() as usize, 6, self.top);
+        self.draw_preview(&mut terminal);
+        terminal.flush();
 
+        self.terminal = terminal;
+    }
 
-            term::color(36) + &term::bold(),
+    fn next_frame (&mut self) {
+        let max = self.items[&self.pick].len();
 
-            term::reset() + &term::color(97),
-            term::color(32),
+        self.frame = (self.frame + 1) % max.max(1);
+        self.render();
+    }
 
+    /// Draws the preview from scratch(e.g. after the terminal is resized).
+    fn redraw (&mut self) {
+        self.top = cursor::position().map(|p| p.1).unwrap_or(0);
+        self.terminal.invalidate();
 
-            term::color(36) + &term::bold(),
+        self.render();
+    }
 
-            term::reset() + &term::color(97),
-            term::color(32),
+    /// Handles mouse events. Returns `true` if the preview should be redrawn.
+    fn mouse (&mut self, mouse: MouseEvent) -> bool {
+        let terminal_w: u16 = layout::terminal_width();
+
+        match mouse.kind {
+            // The 󰸽 & 󰹁 indicators are at the end of the header, the
+            // spaces next to them are part of their area.
+            MouseEventKind::Down(MouseButton::Left) if mouse.row == self.top => {
+                let from_end = terminal_w.saturating_sub(mouse.column) as usize;
+                let prev_size = width::width("󰹁 ");
+                let next_size = prev_size + width::width(" 󰸽");
+
+                if (prev_size + 1..=next_size).contains(&from_end) {
+                    self.next_loader();
+                } else if (1..=prev_size).contains(&from_end) {
+                    self.prev_loader();
+                } else {
+                    return false;
+                }
 
+                self.frame = 0_usize;
+                true
+            },
+            // Scrubbing through the frames with the stats line.
+            MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left) if mouse.row == self.top + 4 => {
+                let max = self.items[&self.pick].len();
+
+                self.frame = (mouse.column as usize * max / terminal_w.max(1) as usize).min(max.saturating_sub(1));
+                true
+            },
+            MouseEventKind::ScrollUp if self.interval < 1000 => {
+                self.interval += 50;
+                true
+            },
+            MouseEventKind::ScrollDown if self.interval > 100 => {
+                self.interval -= 50;
+                true
+            },
+            _ => false
+        }
+    }
 
-            term::color(36) + &term::bold(),
+    /// Opens the editor for the current spinner. Saved changes are
+    /// applied to the spinner list.
+    fn edit (&mut self) {
+        let frames = self.items.get(&self.pick).cloned().unwrap_or_default();
 
-            term::reset() + &term::color(97),
-            term::color(32),
+        // Spinners from a file are saved back under their name in it(like
+        // `validate --fix`), built-in ones are copied to `--source`.
+        let (name, source) = match self.meta.get(&self.pick) {
+            Some(meta) if meta.pack != "builtin" => (meta.name.to_owned(), Some(meta.source.to_owned())),
+            _ => (self.pick.to_owned(), self.source.clone())
+        };
 
+        let mut editor = editor::Editor::new(&name, &frames, source, self.interval, self.keymap.clone(), self.theme.clone());
 
-            term::color(36) + &term::bold(),
+        if let Some(saved) = editor.run() {
+            self.items.insert(self.pick.to_owned(), saved);
+        }
+    }
 
-            term::reset() + &term::color(97),
-            term::color(32),
+    /// Opens the comparison view. The current spinner & the one after it
+    /// are compared when no spinners were chosen.
+    fn compare (&mut self) {
+        let keys = self.keys.clone();
 
+        if self.compare.is_empty() {
+            let next = keys.iter().position(|k| k == &self.pick).map(|p| (p + 1) % keys.len()).unwrap_or(0);
+            self.compare = vec![ self.pick.to_owned(), keys[next].to_owned() ];
+        }
 
-            term::color(36) + &term::bold(),
+        // The panels show the frames the preview does.
+        let items: Cow<json::SpinnJSON> = if self.padded {
+            Cow::Owned(self.items.keys().map(|name| (name.to_owned(), self.frames(name).into_owned())).collect())
+        } else {
+            Cow::Borrowed(&self.items)
+        };
 
-            term::reset() + &term::color(97),
-            term::color(32),
-        );
-        execute!(stdout(), MoveDown(1), MoveToColumn(0)).ok();
+        let mut cmp = compare::Compare::new(&items, keys, &self.compare, self.interval, &self.keymap, &self.theme);
+        cmp.run();
 
-        if self.frame + 1 < max {
-            self.frame += 1;
-        } else {
-            self.frame = 0;
-        }
+        self.compare = cmp.names();
+        self.interval = cmp.interval();
     }
 
-    fn show (&mut self) {
-        println!("");
-        execute!(stdout(), cursor::SavePosition, Hide).ok();
+    /// Stores the current spinner & interval for `--last`.
+    fn remember (&mut self) {
+        self.state.last = Some(self.pick.to_owned());
+        self.state.interval = Some(self.interval);
+        self.state.add_recent(&self.pick);
+        self.state.write().ok();
+    }
 
-        let mut interval: u64 = self.interval as u64;
+    /// Draws the preview again after a sub view(editor, comparison) was closed.
+    fn restore_preview (&mut self) {
+        for _ in 0..6 {
+            println!();
+        }
 
-        println!("");
-        println!("");
-        println!("");
-        println!("");
-        println!("");
-        println!("");
+        self.frame = 0_usize;
 
         self.clear_output();
-        self.next_frame();
+        self.redraw();
+    }
 
-        loop {
-            //|fS "chunk: Main loop"
+    fn show (&mut self) {
+        println!();
+        execute!(stdout(), cursor::SavePosition, Hide, EnableMouseCapture).ok();
 
-            if event::poll(Duration::from_millis(interval)).unwrap() {
-                if let Event::Key(key) = event::read().unwrap() {
-                    if key.code == KeyCode::Char('d') && key.modifiers == KeyModifiers::CONTROL {
-                        execute!(stdout(), Show).ok();
-                        self.clear_output();
-                        return;
-                    } else if key.code == KeyCode::Char('q') {
-                        execute!(stdout(), Show).ok();
-                        self.clear_output();
-                        return;
-                    } else if key.code == KeyCode::Char('h') && interval > 100 {
-                        self.interval -= 50;
-                        interval -= 50;
+        println!();
+        println!();
+        println!();
+        println!();
+        println!();
+        println!();
 
-                        self.clear_output();
-                        self.next_frame();
-                    } else if key.code == KeyCode::Char('j') {
-                        self.next_loader();
-                        self.clear_output();
+        self.clear_output();
+        self.redraw();
 
-                        self.frame = 0_usize;
-                        self.next_frame();
-                    } else if key.code == KeyCode::Char('k') {
-                        self.prev_loader();
-                        self.clear_output();
+        // Started with `--compare`
+        if !self.compare.is_empty() {
+            self.clear_output();
+            self.compare();
+            self.restore_preview();
+        }
 
-                        self.frame = 0_usize;
-                        self.next_frame();
-                    } else if key.code == KeyCode::Char('l') && interval < 1000 {
-                        self.interval += 50;
-                        interval += 50;
+        // Frames are advanced on their own schedule, input only redraws
+        // the current frame.
+        let mut ticker = ticker::Ticker::new(self.interval);
 
+        loop {
+            //|fS "chunk: Main loop"
+
+            if event::poll(ticker.remaining()).unwrap() {
+                match event::read().unwrap() {
+                    Event::Key(key) if self.search.is_some() => {
+                        self.search_key(&key);
+                        self.render();
+                    },
+                    Event::Key(key) => {
+                        match self.keymap.action(keymap::Scope::Preview, &key) {
+                            Some(Action::Quit) => {
+                                execute!(stdout(), Show, DisableMouseCapture).ok();
+                                self.clear_output();
+                                self.remember();
+                                return;
+                            },
+                            Some(Action::Edit) => {
+                                self.clear_output();
+                                self.edit();
+                                self.restore_preview();
+
+                                ticker.reset(self.interval);
+                                continue;
+                            },
+                            Some(Action::Compare) => {
+                                self.clear_output();
+                                self.compare();
+                                self.restore_preview();
+
+                                ticker.reset(self.interval);
+                                continue;
+                            },
+                            Some(action) if self.preview_action(action) => {
+                                self.render();
+                            },
+                            _ => {}
+                        }
+                    },
+                    Event::Resize(_, _) => {
                         self.clear_output();
-                        self.next_frame();
-                    } else {
-                        self.clear_output();
-                        self.next_frame();
-                    }
+                        self.redraw();
+                    },
+                    Event::Mouse(mouse) if self.mouse(mouse) => {
+                        self.render();
+                    },
+                    _ => {}
                 }
-            } else {
-                self.clear_output();
+            }
+
+            if ticker.due(self.interval) {
                 self.next_frame();
             }
 
@@ -582,62 +798,383 @@ impl Spinn {
     }
 }
 
+/// Stops when the output can't be written. A closed pipe(e.g.
+/// `spinn-rs list | head`) isn't an error.
+fn check_output<T> (result: io::Result<T>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => std::process::exit(0),
+        Err(e) => {
+            eprintln!("Error: Failed to write the output: {}", e);
+            std::process::exit(1);
+        },
+    }
+}
+
 fn main() -> std::io::Result<()> {
-    let config = args::get_config();
-    let spinners = json::read_config(config.source);
+    let (config, arg_errors) = args::get_config();
+
+    // Arguments override the defaults from the config file
+    let (user_config, config_warning) = config::read_config();
+    let (defaults, default_warnings) = config::defaults(&user_config);
+
+    // Colors need to be set up before the theme is created
+    let color_mode = config.color.clone().or(defaults.color).unwrap_or_else(|| "auto".to_owned());
+    let (color_level, styles) = term::detect(&color_mode);
+    term::set_output(color_level, styles);
+
+    // Sources from the config file are read first
+    let sources: Vec<String> = defaults.sources.iter().chain(&config.sources).cloned().collect();
+    let policy = config.conflicts.or(defaults.conflicts).unwrap_or_default();
+    let mut catalog = json::read_config(&sources, policy);
+
+    let (keymap, mut warnings) = keymap::Keymap::from_config(user_config.get("keymap"));
+
+    let theme_name = config.theme.or(defaults.theme).unwrap_or_else(|| "dark".to_owned());
+    let (theme, theme_warnings) = theme::Theme::from_config(&theme_name, user_config.get("themes"));
+
+    // Some fonts draw Nerd Font icons in 2 cells
+    if let Some(icon_width) = user_config.get("icon_width").and_then(|v| v.as_u64()) {
+        width::set_icon_width(icon_width as usize);
+    }
+
+    warnings.extend(config_warning);
+    warnings.append(&mut catalog.warnings);
+
+    // `validate` reports the invalid entries as problems.
+    let invalid = std::mem::take(&mut catalog.invalid);
+
+    if config.command != Some(args::Command::Validate) {
+        warnings.extend(invalid.iter().map(|i| format!("`{}` in {} {}", i.name, i.source, i.problem)));
+    }
+
+    if policy != json::Conflict::Error {
+        warnings.append(&mut catalog.conflicts);
+    }
+    warnings.extend(default_warnings);
+    warnings.extend(theme_warnings);
+
+    // The completion scripts only want the names.
+    if config.command != Some(args::Command::Spinners) {
+        for warning in warnings {
+            eprintln!("{}", theme.warning.paint(format!("Warn: {}", warning)));
+        }
+    }
+
+    if !arg_errors.is_empty() {
+        for error in arg_errors {
+            eprintln!("{}", theme.error.paint(format!("Error: {}", error)));
+        }
+
+        std::process::exit(2);
+    }
+
+    if !catalog.conflicts.is_empty() {
+        for conflict in catalog.conflicts {
+            eprintln!("{}", theme.error.paint(format!("Error: {}", conflict)));
+        }
+
+        std::process::exit(1);
+    }
+
+    let state = state::State::read();
+
+    // `--last` only fills in what wasn't given.
+    let (last_pick, last_interval) = match config.last {
+        Some(true) => (state.last.clone(), state.interval),
+        _ => (None, None),
+    };
+
+    // `list` takes a pattern, `completions` a shell & `config` an action
+    // instead of a spinner.
+    let pattern = config.pick.clone().filter(|_| config.command == Some(args::Command::List));
+    let operand = config.pick.clone().filter(|_| matches!(config.command, Some(args::Command::Completions | args::Command::Config)));
+    // Unknown names that were given open the search instead.
+    let named = config.pick.is_some() || defaults.pick.is_some();
+    let pick = config.pick.or(last_pick).or(defaults.pick).unwrap_or_else(|| "default".to_owned());
+    let pick = catalog.resolve(&pick).unwrap_or(pick);
+    let suggestions: Vec<String> = if catalog.items.contains_key(&pick) {
+        Vec::new()
+    } else {
+        let names = catalog.items.keys().chain(catalog.aliases.keys()).map(|name| name.as_str());
+        suggest::matches(&pick, names).into_iter().take(3).map(|name| name.to_owned()).collect()
+    };
+    let compare: Vec<String> = config.compare.unwrap_or_default().into_iter().map(|name| catalog.resolve(&name).unwrap_or(name)).collect();
 
     let mut fd = Spinn {
-        interval: match config.interval {
-            Some(v) => v,
-            None => 100,
-        },
+        interval: config.interval.or(last_interval).or(defaults.interval).or_else(|| catalog.meta.get(&pick)?.interval).unwrap_or(100),
 
         frame: 0,
-        pick: match config.pick {
-            Some(v) => v,
-            None => "default".to_owned(),
-        },
-        items: spinners,
+        pick,
+        items: catalog.items,
+        meta: catalog.meta,
+
+        export: config.export_as.or(defaults.export_as),
+        multi_line: config.multi_line.or(defaults.multi_line),
+        quote: config.quote.or(defaults.quote).unwrap_or('"'),
+        // The editor saves to the last `--source` file.
+        source: config.sources.last().filter(|path| !Path::new(path).is_dir()).cloned(),
+        top: 0,
+        keymap,
+        theme,
+        compare: Vec::new(),
+        state,
+        favorites_only: false,
+        terminal: render::Terminal::default(),
+        sort: config.sort.or(defaults.sort).unwrap_or_default(),
+        keys: Vec::new(),
+        search: None,
+        padded: config.pad.or(defaults.pad).is_some(),
+        align: config.pad.or(defaults.pad).unwrap_or_default(),
+        fill: config.fill.or(defaults.fill).unwrap_or(' '),
+    };
+
+    fd.sort_keys();
 
-        export: config.export_as,
-        multi_line: config.multi_line,
-        quote: match config.quote {
-            Some(v) => v,
-            None => '"',
+    // Unknown spinners can't be compared
+    for name in compare {
+        if fd.items.contains_key(&name) {
+            fd.compare.push(name);
+        } else {
+            let found: Vec<&str> = suggest::matches(&name, fd.items.keys().map(|key| key.as_str())).into_iter().take(3).collect();
+            eprintln!("{}", fd.theme.warning.paint(format!("Warn: Spinner `{}` not found! It won't be compared.{}", name, suggest::did_you_mean(&found))));
         }
-    };
+    }
+
+    if config.show_help.is_some() {
+        check_output(fd.help());
+        return Ok(());
+    }
+
+    let command = config.command.unwrap_or(args::Command::Play);
+
+    match command {
+        args::Command::List => {
+            let filter = commands::Filter {
+                pattern,
+                tags: config.tags,
+                contains: config.contains,
+                max_width: config.max_width,
+            };
+
+            match fd.list(&filter, config.format.unwrap_or(args::Format::Plain)) {
+                Err(error) if error.kind() == io::ErrorKind::InvalidInput => {
+                    eprintln!("{}", fd.theme.error.paint(format!("Error: {}", error)));
+                    std::process::exit(2);
+                },
+                result => check_output(result),
+            }
+
+            return Ok(());
+        },
+        args::Command::Validate => {
+            if !check_output(fd.validate(config.fix.is_some(), &sources, &invalid)) {
+                std::process::exit(1);
+            }
+
+            return Ok(());
+        },
+        args::Command::Completions => {
+            match completions::script(operand.as_deref().unwrap_or_default()) {
+                Ok(script) => check_output(write!(stdout().lock(), "{}", script)),
+                Err(error) => {
+                    eprintln!("{}", fd.theme.error.paint(format!("Error: {}", error)));
+                    std::process::exit(2);
+                },
+            }
+
+            return Ok(());
+        },
+        args::Command::Config => {
+            match (operand.as_deref(), config::config_path()) {
+                (Some("path"), Some(path)) => check_output(writeln!(stdout().lock(), "{}", path.display())),
+                (Some("path"), None) => {
+                    eprintln!("{}", fd.theme.error.paint("Error: No config directory found!"));
+                    std::process::exit(1);
+                },
+                (operand, _) => {
+                    eprintln!("{}", fd.theme.error.paint(format!("Error: Unknown config action `{}`! Expected `path`.", operand.unwrap_or_default())));
+                    std::process::exit(2);
+                },
+            }
+
+            return Ok(());
+        },
+        args::Command::Schema => {
+            check_output(writeln!(stdout().lock(), "{}", serde_json::to_string_pretty(&json::schema()).unwrap_or_default()));
+            return Ok(());
+        },
+        args::Command::Spinners => {
+            let mut out = stdout().lock();
+
+            for name in &fd.keys {
+                check_output(writeln!(out, "{}", name));
+            }
+
+            return Ok(());
+        },
+        args::Command::Play => {},
+        // The rest need a spinner
+        _ if !fd.items.contains_key(&fd.pick) => {
+            let suggestions: Vec<&str> = suggestions.iter().map(|name| name.as_str()).collect();
+
+            eprintln!("{}", fd.theme.error.paint(format!("Error: Spinner `{}` not found!{}", fd.pick, suggest::did_you_mean(&suggestions))));
+            std::process::exit(1);
+        },
+        args::Command::Show => {
+            check_output(fd.strip());
+            return Ok(());
+        },
+        args::Command::Info => {
+            check_output(fd.info());
+            return Ok(());
+        },
+        args::Command::Export => {
+            fd.export.get_or_insert(export::SpinnExport::List);
+            check_output(fd.export());
+            return Ok(());
+        },
+    }
+
+    // Editing a spinner(new spinners are created when saved)
+    if config.edit.is_some() {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), Hide, EnableMouseCapture).ok();
+
+        fd.edit();
+
+        execute!(stdout(), Show, DisableMouseCapture).ok();
+        terminal::disable_raw_mode()?;
+        return Ok(());
+    }
 
     // Invalid item pick
-    if fd.items.contains_key(&fd.pick) == false {
-        if let Some(random_key) = fd.items.keys().next() {
-            println!(
-                "{}Error: Spinner not found! A random one has been picked instead.",
-                term::color(31)
-            );
-            fd.pick = random_key.to_owned();
+    if !fd.items.contains_key(&fd.pick) {
+        if named && !fd.keys.is_empty() {
+            let query = fd.pick.to_owned();
+            fd.start_search(&query);
+        } else if let Some(first) = fd.keys.first() {
+            fd.pick = first.to_owned();
         } else {
-            println!(
-                "{}Warn: No spinner found! Pleass provide at least 1 spinner.",
-                term::color(31)
-            );
-            fd.help();
+            eprintln!("{}", fd.theme.warning.paint("Warn: No spinner found! Pleass provide at least 1 spinner."));
+            check_output(fd.help());
             return Ok(());
         }
     }
 
-    if fd.items.keys().len() == 0 || config.show_help != None {
-        fd.help();
+    if fd.items.keys().len() == 0 {
+        check_output(fd.help());
     } else {
         terminal::enable_raw_mode()?;
-        fd.show();
+
+        if config.fullscreen.is_some() {
+            fd.fullscreen();
+        } else {
+            fd.show();
+        }
+
         terminal::disable_raw_mode()?;
     }
 
-    print!(
-        "{}",
-        term::color(97),
-    );
-
-    fd.export();
+    check_output(fd.export());
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Preview of `items` with the default settings, the first spinner
+    /// is picked.
+    pub(crate) fn spinn (items: &[(&str, &[&str])]) -> Spinn {
+        let items: json::SpinnJSON = items.iter().map(|(name, frames)| {
+            (name.to_string(), frames.iter().map(|f| f.to_string()).collect())
+        }).collect();
+
+        let mut fd = Spinn {
+            interval: 100,
+            pick: items.keys().next().cloned().unwrap_or_default(),
+            frame: 0,
+            export: None,
+            multi_line: None,
+            quote: '"',
+            source: None,
+            top: 0,
+            keymap: keymap::Keymap::from_config(None).0,
+            theme: theme::Theme::from_config("dark", None).0,
+            compare: Vec::new(),
+            state: state::State::default(),
+            favorites_only: false,
+            terminal: render::Terminal::default(),
+            items,
+            sort: args::Sort::default(),
+            keys: Vec::new(),
+            search: None,
+            padded: false,
+            align: width::Align::default(),
+            fill: ' ',
+            meta: HashMap::new(),
+        };
+
+        fd.sort_keys();
+        fd
+    }
+
+    /// Lines of the preview drawn `width` cells wide.
+    fn preview (fd: &Spinn, width: usize) -> Vec<String> {
+        let mut buffer = render::Buffer::new(width, 6);
+        fd.draw_preview(&mut buffer);
+
+        buffer.to_string().lines().map(|l| l.to_owned()).collect()
+    }
+
+    #[test]
+    fn preview_fits_the_width () {
+        let fd = spinn(&[ ("dots", &["⠋", "⠙", "⠹"]) ]);
+
+        for w in [ 20, 40, 80 ] {
+            let lines = preview(&fd, w);
+
+            assert_eq!(lines.len(), 6);
+            assert!(lines.iter().all(|l| width::width(l) == w), "{:?}", lines);
+            assert!(lines[0].contains("dots"));
+            assert_eq!(lines[2].trim(), "⠋");
+        }
+
+        // The stats & the labels are only shown when they fit.
+        assert!(preview(&fd, 80)[0].contains("Style: "));
+        assert!(!preview(&fd, 20)[0].contains("Style: "));
+        assert!(preview(&fd, 80)[4].contains("Frames: 3"));
+    }
+
+    #[test]
+    fn preview_wide_spinner () {
+        let fd = spinn(&[ ("clock", &["時計時計", "計時計時"]) ]);
+
+        for w in [ 20, 40, 80 ] {
+            let lines = preview(&fd, w);
+
+            assert!(lines.iter().all(|l| width::width(l) == w), "{:?}", lines);
+            assert_eq!(lines[2].trim(), "時計時計");
+        }
+
+        // Frames that don't fit are cut without splitting a grapheme.
+        let lines = preview(&fd, 6);
+        assert!(lines.iter().all(|l| width::width(l) == 6), "{:?}", lines);
+        assert_eq!(lines[2].trim(), "時計…");
+    }
+
+    #[test]
+    fn preview_empty_spinner () {
+        let fd = spinn(&[ ("empty", &[]) ]);
+
+        for w in [ 20, 40, 80 ] {
+            let lines = preview(&fd, w);
+
+            assert!(lines.iter().all(|l| width::width(l) == w), "{:?}", lines);
+            assert_eq!(lines[2].trim(), "");
+        }
+
+        assert!(preview(&fd, 80)[4].contains("Frames: 0"));
+    }
+}
@@ -0,0 +1,230 @@
+// Drawing the UI
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::Print,
+    terminal::{BeginSynchronizedUpdate, EndSynchronizedUpdate}
+};
+use std::{fmt, io::{stdout, Write}};
+
+use crate::{term::{self, Style}, width::{self, Segment}};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    /// Grapheme shown in the cell. Cells covered by a wide grapheme
+    /// have an empty symbol.
+    pub symbol: String,
+    /// Escape sequence applied to the symbol, kept as text since colored
+    /// spinners can add their own sequences.
+    pub style: String,
+}
+
+impl Default for Cell {
+    fn default () -> Cell {
+        Cell { symbol: " ".to_owned(), style: String::new() }
+    }
+}
+
+/// Something the UI can be drawn on.
+pub trait Renderer {
+    /// Width & height of the drawing area.
+    fn size (&self) -> (usize, usize);
+
+    /// Writes `text` at `x`, `y`. Text outside of the drawing area is
+    /// dropped. Returns the column after the text.
+    fn print (&mut self, x: usize, y: usize, text: &str, style: &Style) -> usize;
+
+    /// Shows what was drawn.
+    fn flush (&mut self) {}
+}
+
+/// Grid of cells kept in memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Buffer {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    pub fn new (width: usize, height: usize) -> Buffer {
+        Buffer {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+        }
+    }
+
+    pub fn get (&self, x: usize, y: usize) -> Option<&Cell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.cells.get(y * self.width + x)
+    }
+}
+
+impl Renderer for Buffer {
+    fn size (&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn print (&mut self, x: usize, y: usize, text: &str, style: &Style) -> usize {
+        let mut column = x;
+        // Styles from escape sequences in the text are added to `style`.
+        let base = style.sequence();
+        let mut current = base.to_owned();
+
+        for segment in width::segments(text) {
+            let grapheme = match segment {
+                Segment::Escape(sequence) if sequence == "\x1b[0m" || sequence == "\x1b[m" => {
+                    current = base.to_owned();
+                    continue;
+                },
+                Segment::Escape(sequence) => {
+                    if sequence.ends_with('m') {
+                        current += sequence;
+                    }
+
+                    continue;
+                },
+                Segment::Grapheme(grapheme) => grapheme,
+            };
+            let size = width::grapheme_width(grapheme);
+
+            if size == 0 {
+                continue;
+            }
+
+            if y < self.height && column + size <= self.width {
+                self.cells[y * self.width + column] = Cell { symbol: grapheme.to_owned(), style: current.to_owned() };
+
+                for covered in column + 1..column + size {
+                    self.cells[y * self.width + covered] = Cell { symbol: String::new(), style: current.to_owned() };
+                }
+            } else if y < self.height && column < self.width {
+                // Wide graphemes that don't fit are replaced with spaces.
+                for covered in column..self.width {
+                    self.cells[y * self.width + covered] = Cell { symbol: " ".to_owned(), style: current.to_owned() };
+                }
+            }
+
+            column += size;
+        }
+
+        column
+    }
+}
+
+/// Text of the buffer without any styles, one line per row.
+impl fmt::Display for Buffer {
+    fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            let line: String = self.cells[y * self.width..(y + 1) * self.width].iter().map(|c| c.symbol.as_str()).collect();
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Draws on the terminal. Only the cells that changed since the last
+/// flush are written.
+#[derive(Debug)]
+pub struct Terminal {
+    buffer: Buffer,
+    previous: Option<Buffer>,
+    /// Row where the drawing area starts.
+    top: u16,
+}
+
+impl Default for Terminal {
+    fn default () -> Terminal {
+        Terminal {
+            buffer: Buffer::new(0, 0),
+            previous: None,
+            top: 0,
+        }
+    }
+}
+
+impl Terminal {
+    /// Starts drawing a `width`x`height` area at row `top`.
+    pub fn begin (&mut self, width: usize, height: usize, top: u16) {
+        if top != self.top {
+            self.previous = None;
+        }
+
+        self.buffer = Buffer::new(width, height);
+        self.top = top;
+    }
+
+    /// Makes the next flush draw everything(e.g. after the area was cleared).
+    pub fn invalidate (&mut self) {
+        self.previous = None;
+    }
+}
+
+impl Renderer for Terminal {
+    fn size (&self) -> (usize, usize) {
+        self.buffer.size()
+    }
+
+    fn print (&mut self, x: usize, y: usize, text: &str, style: &Style) -> usize {
+        self.buffer.print(x, y, text, style)
+    }
+
+    /// The cursor is left at the start of the line after the drawing area.
+    fn flush (&mut self) {
+        let mut out = stdout().lock();
+
+        let buffer = &self.buffer;
+        let top = self.top;
+
+        let previous = self.previous.take().filter(|p| p.width == buffer.width && p.height == buffer.height);
+        let changed = |x: usize, y: usize| -> bool {
+            match &previous {
+                Some(p) => p.get(x, y) != buffer.get(x, y),
+                None => true,
+            }
+        };
+
+        queue!(out, BeginSynchronizedUpdate).ok();
+
+        for y in 0..buffer.height {
+            let mut x = 0;
+
+            while x < buffer.width {
+                if !changed(x, y) {
+                    x += 1;
+                    continue;
+                }
+
+                // Runs of changed cells are written together.
+                let mut style = "";
+                let mut text = String::new();
+
+                queue!(out, MoveTo(x as u16, top + y as u16)).ok();
+
+                while x < buffer.width && changed(x, y) {
+                    let cell = &buffer.cells[y * buffer.width + x];
+
+                    if cell.style != style {
+                        text += &(term::reset() + &cell.style);
+                        style = &cell.style;
+                    }
+
+                    text += &cell.symbol;
+                    x += 1;
+                }
+
+                queue!(out, Print(text + &term::reset())).ok();
+            }
+        }
+
+        queue!(out, MoveTo(0, top + buffer.height as u16), EndSynchronizedUpdate).ok();
+        out.flush().ok();
+
+        self.previous = Some(buffer.clone());
+    }
+}
@@ -0,0 +1,87 @@
+// Search prompt of the preview
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{render::Renderer, suggest, Spinn};
+
+impl Spinn {
+    /// Opens the search prompt with `query` & jumps to the best match.
+    pub fn start_search (&mut self, query: &str) {
+        self.search = Some(query.to_owned());
+        self.update_search();
+    }
+
+    /// Filters the spinners with the search query & picks the best match.
+    fn update_search (&mut self) {
+        self.sort_keys();
+
+        let Some(query) = &self.search else {
+            return;
+        };
+
+        let best = self.keys.iter()
+            .filter_map(|key| Some((suggest::score(query, key)?, key)))
+            .min_by_key(|(score, _)| *score)
+            .map(|(_, key)| key);
+
+        // Without matches the current spinner is kept(if it exists).
+        let pick = match best {
+            Some(key) => Some(key),
+            None if self.keys.contains(&self.pick) => None,
+            None => self.keys.first(),
+        };
+
+        if let Some(pick) = pick {
+            self.pick = pick.to_owned();
+            self.frame = 0_usize;
+        }
+    }
+
+    /// Handles a key while the search prompt is open. `Enter` & `Esc`
+    /// close it, the matched spinner stays selected.
+    pub fn search_key (&mut self, key: &KeyEvent) {
+        let Some(query) = &mut self.search else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => {
+                self.search = None;
+                self.sort_keys();
+            },
+            KeyCode::Down => {
+                self.next_loader();
+                self.frame = 0_usize;
+            },
+            KeyCode::Up => {
+                self.prev_loader();
+                self.frame = 0_usize;
+            },
+            KeyCode::Backspace => {
+                query.pop();
+                self.update_search();
+            },
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                query.push(c);
+                self.update_search();
+            },
+            _ => {}
+        }
+    }
+
+    /// Draws the search prompt at `x`, `y`.
+    pub fn draw_search (&self, r: &mut dyn Renderer, x: usize, y: usize) {
+        let t = &self.theme;
+        let query = self.search.as_deref().unwrap_or_default();
+        let count = self.keys.iter().filter(|key| suggest::score(query, key).is_some()).count();
+
+        let x = r.print(x, y, "󰍉 ", &t.accent);
+        let x = r.print(x, y, query, &t.value);
+        let x = r.print(x, y, "▏", &t.accent);
+
+        if count == 0 {
+            r.print(x + 1, y, "No matches", &t.error);
+        } else {
+            r.print(x + 1, y, &format!("{} match(es)", count), &t.muted);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// State remembered between runs
+use serde_json::{json, Value};
+use std::{env, fs, io, path::PathBuf};
+
+/// Number of spinners kept in `recent`.
+const RECENT_SIZE: usize = 50;
+
+/// Directory containing the state file.
+///
+/// Uses `$XDG_DATA_HOME/spinn-rs`, `~/.local/share/spinn-rs` or `%APPDATA%\spinn-rs`.
+pub fn data_dir () -> Option<PathBuf> {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(v) if !v.is_empty() => PathBuf::from(v),
+        _ => match env::var_os("HOME") {
+            Some(v) if !v.is_empty() => PathBuf::from(v).join(".local").join("share"),
+            _ => PathBuf::from(env::var_os("APPDATA")?),
+        },
+    };
+
+    Some(base.join("spinn-rs"))
+}
+
+pub fn state_path () -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("state.json"))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    /// Last previewed spinner.
+    pub last: Option<String>,
+    /// Interval used for the last spinner.
+    pub interval: Option<u32>,
+    pub favorites: Vec<String>,
+    /// Previewed spinners, the most recent first.
+    pub recent: Vec<String>,
+}
+
+impl State {
+    /// Reads the state file. Missing or invalid files give an empty state.
+    pub fn read () -> State {
+        let Some(state_txt) = state_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return State::default();
+        };
+        let Ok(state) = serde_json::from_str::<Value>(&state_txt) else {
+            return State::default();
+        };
+
+        State {
+            last: state.get("last").and_then(|v| v.as_str()).map(|v| v.to_owned()),
+            interval: state.get("interval").and_then(|v| v.as_u64()).and_then(|v| u32::try_from(v).ok()),
+            favorites: state.get("favorites").and_then(|v| v.as_array()).map(|list| {
+                list.iter().filter_map(|v| v.as_str()).map(|v| v.to_owned()).collect()
+            }).unwrap_or_default(),
+            recent: state.get("recent").and_then(|v| v.as_array()).map(|list| {
+                list.iter().filter_map(|v| v.as_str()).map(|v| v.to_owned()).collect()
+            }).unwrap_or_default(),
+        }
+    }
+
+    pub fn write (&self) -> io::Result<()> {
+        let Some(path) = state_path() else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "no data directory"));
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let state = json!({
+            "last": self.last,
+            "interval": self.interval,
+            "favorites": self.favorites,
+            "recent": self.recent,
+        });
+
+        fs::write(path, serde_json::to_string_pretty(&state)? + "\n")
+    }
+
+    pub fn is_favorite (&self, name: &str) -> bool {
+        self.favorites.iter().any(|f| f == name)
+    }
+
+    /// Moves `name` to the front of the recently previewed spinners.
+    pub fn add_recent (&mut self, name: &str) {
+        self.recent.retain(|r| r != name);
+        self.recent.insert(0, name.to_owned());
+        self.recent.truncate(RECENT_SIZE);
+    }
+
+    /// Adds `name` to the favorites, or removes it if it's already there.
+    pub fn toggle_favorite (&mut self, name: &str) {
+        match self.favorites.iter().position(|f| f == name) {
+            Some(i) => {
+                self.favorites.remove(i);
+            },
+            None => self.favorites.push(name.to_owned()),
+        }
+    }
+}
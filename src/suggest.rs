@@ -0,0 +1,74 @@
+// Suggestions for mistyped names
+
+/// Number of single character edits needed to turn `a` into `b`.
+pub fn distance (a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Candidate closest to `name`, if any of them is close enough to be
+/// what was meant.
+pub fn closest<'a> (name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = (name.chars().count() / 3).max(1);
+
+    candidates.into_iter()
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(d, _)| *d <= limit)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, candidate)| candidate)
+}
+
+/// How well `name` matches a search for `query`, lower is better. Names
+/// starting with or containing `query` come first, then the ones close
+/// enough to be a typo.
+pub fn score (query: &str, name: &str) -> Option<usize> {
+    let query = query.to_lowercase();
+    let name = name.to_lowercase();
+
+    if name.starts_with(&query) {
+        return Some(0);
+    }
+
+    if name.contains(&query) {
+        return Some(1);
+    }
+
+    let limit = (query.chars().count() / 3).max(1);
+    let d = distance(&query, &name);
+
+    (d <= limit).then_some(2 + d)
+}
+
+/// Candidates matching `query`, the best matches first.
+pub fn matches<'a> (query: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut found: Vec<(usize, &str)> = candidates.into_iter()
+        .filter_map(|candidate| Some((score(query, candidate)?, candidate)))
+        .collect();
+
+    found.sort_by_key(|(s, _)| *s);
+    found.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// ` Did you mean ...?` listing `names`, empty if there are none.
+pub fn did_you_mean (names: &[&str]) -> String {
+    let names: Vec<String> = names.iter().map(|name| format!("`{}`", name)).collect();
+
+    match names.as_slice() {
+        [] => String::new(),
+        [name] => format!(" Did you mean {}?", name),
+        [rest @ .., last] => format!(" Did you mean {} or {}?", rest.join(", "), last),
+    }
+}
@@ -1,75 +1,371 @@
 // Styler for the terminal
-#![allow(dead_code)]
+use std::{env, fmt, io::{stdout, IsTerminal}, sync::atomic::{AtomicBool, AtomicU8, Ordering}};
 
-pub fn rgb (r: u32, g: u32, b: u32) -> String {
-    format!("\x1b[38;2;{};{};{}m", r, g, b)
+/// Colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    None,
+    /// The 16 ANSI colors.
+    Basic,
+    Ansi256,
+    TrueColor,
 }
 
-pub fn color (n: u32) -> String {
-    format!("\x1b[{}m", n)
+static COLOR_LEVEL: AtomicU8 = AtomicU8::new(ColorLevel::TrueColor as u8);
+/// Whether bold, italic etc. are used.
+static STYLES: AtomicBool = AtomicBool::new(true);
+
+pub fn color_level () -> ColorLevel {
+    match COLOR_LEVEL.load(Ordering::Relaxed) {
+        0 => ColorLevel::None,
+        1 => ColorLevel::Basic,
+        2 => ColorLevel::Ansi256,
+        _ => ColorLevel::TrueColor,
+    }
 }
 
-pub fn bg (hex: &str) -> String {
-    let inner = hex.trim_start_matches("#");
+pub fn set_output (level: ColorLevel, styles: bool) {
+    COLOR_LEVEL.store(level as u8, Ordering::Relaxed);
+    STYLES.store(styles, Ordering::Relaxed);
+}
 
-    let r: u32;
-    let g: u32;
-    let b: u32;
+/// Colors supported by the terminal according to `$COLORTERM` & `$TERM`.
+fn supported_level () -> ColorLevel {
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    let term = env::var("TERM").unwrap_or_default();
 
-    match inner.chars().count() {
-        3 => {
-            r = u32::from_str_radix(&inner[0..1], 16).expect("Failed to get value of `r`");
-            g = u32::from_str_radix(&inner[1..2], 16).expect("Failed to get value of `g`");
-            b = u32::from_str_radix(&inner[2..3], 16).expect("Failed to get value of `b`");
-        },
-        6 => {
-            r = u32::from_str_radix(&inner[0..2], 16).expect("Failed to get value of `r`");
-            g = u32::from_str_radix(&inner[2..4], 16).expect("Failed to get value of `g`");
-            b = u32::from_str_radix(&inner[4..6], 16).expect("Failed to get value of `b`");
-        },
+    if colorterm == "truecolor" || colorterm == "24bit" || term.contains("truecolor") || term.contains("direct") {
+        ColorLevel::TrueColor
+    } else if term.contains("256color") {
+        ColorLevel::Ansi256
+    } else if term == "dumb" {
+        ColorLevel::None
+    } else {
+        ColorLevel::Basic
+    }
+}
 
+/// Color level & whether styles should be used for a `--color` value.
+///
+/// `auto` turns everything off when the output isn't a terminal and turns
+/// colors off when `$NO_COLOR` is set.
+pub fn detect (mode: &str) -> (ColorLevel, bool) {
+    match mode {
+        "never" => (ColorLevel::None, false),
+        "always" => (supported_level().max(ColorLevel::Basic), true),
         _ => {
-            r = 255;
-            g = 255;
-            b = 255;
+            if !stdout().is_terminal() {
+                return (ColorLevel::None, false);
+            }
+
+            let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+            let level = supported_level();
+
+            (if no_color { ColorLevel::None } else { level }, level != ColorLevel::None)
         }
     }
+}
 
-    format!("\x1b[48;2;{};{};{}m", r, g, b)
+//|fS "chunk: Downsampling"
+
+/// The 16 ANSI colors, in the order of their codes.
+const BASIC: [(u32, u32, u32); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0), (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0), (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+
+fn to_256 (r: u32, g: u32, b: u32) -> u32 {
+    if r == g && g == b {
+        return match r {
+            0..8 => 16,
+            249.. => 231,
+            _ => 232 + (r - 8) * 24 / 241,
+        };
+    }
+
+    let level = |c: u32| (c * 5 + 127) / 255;
+    16 + 36 * level(r) + 6 * level(g) + level(b)
 }
 
-pub fn fg (hex: &str) -> String {
-    let inner = hex.trim_start_matches("#");
+/// Index of the closest of the 16 ANSI colors.
+fn to_basic (r: u32, g: u32, b: u32) -> u32 {
+    let distance = |(br, bg, bb): (u32, u32, u32)| -> u32 {
+        r.abs_diff(br).pow(2) + g.abs_diff(bg).pow(2) + b.abs_diff(bb).pow(2)
+    };
 
-    let r: u32;
-    let g: u32;
-    let b: u32;
+    (0..16).min_by_key(|i| distance(BASIC[*i as usize])).unwrap_or(7)
+}
 
-    match inner.chars().count() {
-        3 => {
-            r = u32::from_str_radix(&inner[0..1], 16).expect("Failed to get value of `r`");
-            g = u32::from_str_radix(&inner[1..2], 16).expect("Failed to get value of `g`");
-            b = u32::from_str_radix(&inner[2..3], 16).expect("Failed to get value of `b`");
+/// Color of a 256 color palette index.
+fn from_256 (n: u32) -> (u32, u32, u32) {
+    match n {
+        0..16 => BASIC[n as usize],
+        16..232 => {
+            let level = |c: u32| if c == 0 { 0 } else { 55 + c * 40 };
+            (level((n - 16) / 36), level((n - 16) / 6 % 6), level((n - 16) % 6))
         },
-        6 => {
-            r = u32::from_str_radix(&inner[0..2], 16).expect("Failed to get value of `r`");
-            g = u32::from_str_radix(&inner[2..4], 16).expect("Failed to get value of `g`");
-            b = u32::from_str_radix(&inner[4..6], 16).expect("Failed to get value of `b`");
+        _ => {
+            let gray = 8 + (n.min(255) - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// SGR parameters for a color, `layer` is `38` for the foreground &
+/// `48` for the background. Uses the best color the terminal supports.
+fn rgb_parameters (layer: u32, r: u32, g: u32, b: u32) -> Option<String> {
+    match color_level() {
+        ColorLevel::None => None,
+        ColorLevel::Basic => {
+            let index = to_basic(r, g, b);
+            let base = if layer == 38 { 30 } else { 40 };
+
+            Some((if index < 8 { base + index } else { base + 60 + index - 8 }).to_string())
         },
+        ColorLevel::Ansi256 => Some(format!("{};5;{}", layer, to_256(r, g, b))),
+        ColorLevel::TrueColor => Some(format!("{};2;{};{};{}", layer, r, g, b)),
+    }
+}
 
-        _ => {
-            r = 255;
-            g = 255;
-            b = 255;
+/// Rewrites the SGR parameters of an escape sequence for the color level.
+fn adapt_parameters (parameters: &str) -> String {
+    let level = color_level();
+    let parts: Vec<u32> = parameters.split(';').map(|p| p.parse::<u32>().unwrap_or(0)).collect();
+
+    // `38;2;r;g;b` becomes `38;5;n` etc.
+    let convert = |layer: u32, (r, g, b): (u32, u32, u32)| -> String {
+        rgb_parameters(layer, r, g, b).unwrap_or_default()
+    };
+
+    let mut output: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < parts.len() {
+        let part = parts[i];
+
+        match part {
+            38 | 48 if parts.get(i + 1) == Some(&2) && i + 4 < parts.len() => {
+                output.push(convert(part, (parts[i + 2], parts[i + 3], parts[i + 4])));
+                i += 5;
+            },
+            38 | 48 if parts.get(i + 1) == Some(&5) && i + 2 < parts.len() => {
+                match level {
+                    ColorLevel::None => {},
+                    ColorLevel::Basic => output.push(convert(part, from_256(parts[i + 2]))),
+                    _ => output.push(format!("{};5;{}", part, parts[i + 2])),
+                }
+
+                i += 3;
+            },
+            30..=39 | 40..=49 | 90..=97 | 100..=107 => {
+                if level != ColorLevel::None {
+                    output.push(part.to_string());
+                }
+
+                i += 1;
+            },
+            _ => {
+                if STYLES.load(Ordering::Relaxed) {
+                    output.push(part.to_string());
+                }
+
+                i += 1;
+            }
+        }
+    }
+
+    output.retain(|o| !o.is_empty());
+    output.join(";")
+}
+
+/// Makes the escape sequences in `text`(e.g. in colored spinners) use the
+/// colors the terminal supports. They are removed when colors are off.
+pub fn adapt (text: &str) -> String {
+    if !text.contains('\x1b') {
+        return text.to_owned();
+    }
+
+    let mut output = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("\x1b[") {
+        output += &rest[..start];
+        rest = &rest[start + 2..];
+
+        // Final byte of the sequence.
+        let Some(end) = rest.find(|c: char| ('@'..='~').contains(&c)) else {
+            rest = "";
+            break;
+        };
+
+        let parameters = &rest[..end];
+
+        if &rest[end..end + 1] == "m" {
+            let adapted = adapt_parameters(parameters);
+
+            if !adapted.is_empty() {
+                output += &format!("\x1b[{}m", adapted);
+            }
+        } else {
+            output += &format!("\x1b[{}", &rest[..=end]);
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    output + rest
+}
+
+//|fE
+
+//|fS "chunk: Styles"
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// One of the 16 ANSI colors, as it's foreground code(e.g. `34`).
+    Ansi(u8),
+    Rgb(u8, u8, u8),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidHex(pub String);
+
+impl fmt::Display for InvalidHex {
+    fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` isn't a #rgb or #rrggbb color", self.0)
+    }
+}
+
+impl Color {
+    /// Parses a `#rgb` or `#rrggbb` color.
+    pub fn hex (hex: &str) -> Result<Color, InvalidHex> {
+        let inner = hex.trim_start_matches('#');
+        let invalid = || InvalidHex(hex.to_owned());
+
+        if !inner.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let channel = |from: usize, to: usize| u8::from_str_radix(&inner[from..to], 16).map_err(|_| invalid());
+
+        match inner.len() {
+            3 => Ok(Color::Rgb(channel(0, 1)? * 17, channel(1, 2)? * 17, channel(2, 3)? * 17)),
+            6 => Ok(Color::Rgb(channel(0, 2)?, channel(2, 4)?, channel(4, 6)?)),
+            _ => Err(invalid()),
+        }
+    }
+
+    fn parameters (&self, layer: u32) -> Option<String> {
+        match *self {
+            Color::Ansi(_) if color_level() == ColorLevel::None => None,
+            Color::Ansi(code) if layer == 48 => Some((code as u32 + 10).to_string()),
+            Color::Ansi(code) => Some(code.to_string()),
+            Color::Rgb(r, g, b) => rgb_parameters(layer, r as u32, g as u32, b as u32),
+        }
+    }
+}
+
+/// Colors & attributes of a piece of text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub const fn new () -> Style {
+        Style { fg: None, bg: None, bold: false, italic: false, underline: false, reverse: false }
+    }
+
+    /// Style with one of the 16 ANSI colors(e.g. `Style::ansi(34)`).
+    pub const fn ansi (code: u8) -> Style {
+        Style::new().fg(Color::Ansi(code))
+    }
+
+    pub const fn fg (self, color: Color) -> Style {
+        Style { fg: Some(color), ..self }
+    }
+
+    pub const fn bg (self, color: Color) -> Style {
+        Style { bg: Some(color), ..self }
+    }
+
+    pub const fn bold (self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    pub const fn italic (self) -> Style {
+        Style { italic: true, ..self }
+    }
+
+    pub const fn underline (self) -> Style {
+        Style { underline: true, ..self }
+    }
+
+    pub const fn reverse (self) -> Style {
+        Style { reverse: true, ..self }
+    }
+
+    /// `content` drawn with this style, the style is reset after it.
+    pub fn paint<T: fmt::Display> (&self, content: T) -> Styled<T> {
+        Styled { style: *self, content }
+    }
+
+    /// Escape sequence that turns the style on. Empty when nothing
+    /// would change.
+    pub fn sequence (&self) -> String {
+        let mut parameters: Vec<String> = Vec::new();
+
+        if STYLES.load(Ordering::Relaxed) {
+            for (on, code) in [ (self.bold, "1"), (self.italic, "3"), (self.underline, "4"), (self.reverse, "7") ] {
+                if on {
+                    parameters.push(code.to_owned());
+                }
+            }
+        }
+
+        parameters.extend(self.fg.and_then(|c| c.parameters(38)));
+        parameters.extend(self.bg.and_then(|c| c.parameters(48)));
+
+        if parameters.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", parameters.join(";"))
         }
     }
+}
+
+/// Text with a style. The style is reset after the text so it can't leak
+/// into whatever comes next.
+#[derive(Debug, Clone)]
+pub struct Styled<T> {
+    style: Style,
+    content: T,
+}
+
+impl<T: fmt::Display> fmt::Display for Styled<T> {
+    fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sequence = self.style.sequence();
 
-    format!("\x1b[38;2;{};{};{}m", r, g, b)
+        if sequence.is_empty() {
+            write!(f, "{}", self.content)
+        } else {
+            write!(f, "{}{}{}", sequence, self.content, reset())
+        }
+    }
 }
 
-pub fn reset () -> String { "\x1b[0m".to_string() }
-pub fn bold () -> String { "\x1b[1m".to_string() }
+/// Escape sequence that turns every style off.
+pub fn reset () -> String {
+    if STYLES.load(Ordering::Relaxed) || color_level() != ColorLevel::None {
+        "\x1b[0m".to_owned()
+    } else {
+        String::new()
+    }
+}
 
-/// Hello
-pub fn italic () -> String { "\x1b[3m".to_string() }
-pub fn underlined () -> String { "\x1b[4m".to_string() }
+//|fE
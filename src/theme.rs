@@ -0,0 +1,188 @@
+// Colors used by the UI
+use serde_json::Value;
+
+use crate::term::{Color, Style};
+
+/// Built-in themes.
+pub const THEMES: &[&str] = &[ "dark", "light", "high-contrast" ];
+
+/// Styles for each part of the UI.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Normal text.
+    pub text: Style,
+    /// Spinner names & headings.
+    pub title: Style,
+    /// Icons, labels & active indicators.
+    pub accent: Style,
+    /// Less important text & inactive indicators.
+    pub muted: Style,
+    /// Numbers & argument values.
+    pub value: Style,
+    /// Keys & flags.
+    pub key: Style,
+    /// The spinner itself.
+    pub spinner: Style,
+    pub warning: Style,
+    pub error: Style,
+}
+
+impl Theme {
+    pub fn dark () -> Theme {
+        Theme {
+            text: Style::ansi(97),
+            title: Style::ansi(34).bold(),
+            accent: Style::ansi(32),
+            muted: Style::ansi(90),
+            value: Style::ansi(36),
+            key: Style::ansi(33),
+            spinner: Style::ansi(97),
+            warning: Style::ansi(33),
+            error: Style::ansi(31),
+        }
+    }
+
+    pub fn light () -> Theme {
+        Theme {
+            text: Style::ansi(30),
+            title: Style::ansi(34).bold(),
+            accent: Style::ansi(32),
+            muted: Style::ansi(90),
+            value: Style::ansi(35),
+            key: Style::ansi(31),
+            spinner: Style::ansi(30),
+            warning: Style::ansi(33),
+            error: Style::ansi(31),
+        }
+    }
+
+    pub fn high_contrast () -> Theme {
+        Theme {
+            text: Style::ansi(97).bold(),
+            title: Style::ansi(93).bold(),
+            accent: Style::ansi(92).bold(),
+            muted: Style::ansi(37),
+            value: Style::ansi(96).bold(),
+            key: Style::ansi(93).bold(),
+            spinner: Style::ansi(97).bold(),
+            warning: Style::ansi(93).bold(),
+            error: Style::ansi(91).bold(),
+        }
+    }
+
+    pub fn builtin (name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    fn slot_mut (&mut self, slot: &str) -> Option<&mut Style> {
+        match slot {
+            "text" => Some(&mut self.text),
+            "title" => Some(&mut self.title),
+            "accent" => Some(&mut self.accent),
+            "muted" => Some(&mut self.muted),
+            "value" => Some(&mut self.value),
+            "key" => Some(&mut self.key),
+            "spinner" => Some(&mut self.spinner),
+            "warning" => Some(&mut self.warning),
+            "error" => Some(&mut self.error),
+            _ => None,
+        }
+    }
+
+    /// Gets the theme called `name`, user themes are looked up in the
+    /// `themes` table of the config file.
+    ///
+    /// ```json
+    /// { "mine": { "base": "light", "title": "#1e66f5", "spinner": { "fg": "#40a02b", "bg": "#eff1f5", "bold": true } } }
+    /// ```
+    ///
+    /// Returns the theme along with warnings about invalid entries.
+    pub fn from_config (name: &str, themes: Option<&Value>) -> (Theme, Vec<String>) {
+        let mut warnings = Vec::new();
+
+        let Some(user) = themes.and_then(|t| t.get(name)) else {
+            return match Theme::builtin(name) {
+                Some(theme) => (theme, warnings),
+                None => {
+                    warnings.push(format!("Theme `{}` not found! Using `dark` instead.", name));
+                    (Theme::dark(), warnings)
+                }
+            };
+        };
+
+        let Some(user) = user.as_object() else {
+            warnings.push(format!("Theme `{}` should be an object.", name));
+            return (Theme::dark(), warnings);
+        };
+
+        let base = user.get("base").and_then(|b| b.as_str()).unwrap_or("dark");
+        let mut theme = match Theme::builtin(base) {
+            Some(theme) => theme,
+            None => {
+                warnings.push(format!("Base theme `{}` of `{}` isn't a built-in theme.", base, name));
+                Theme::dark()
+            }
+        };
+
+        for (slot, value) in user {
+            if slot == "base" {
+                continue;
+            }
+
+            let Some(target) = theme.slot_mut(slot) else {
+                warnings.push(format!("Unknown slot `{}` in theme `{}`.", slot, name));
+                continue;
+            };
+
+            match style(value) {
+                Ok(style) => *target = style,
+                Err(e) => warnings.push(format!("Invalid color {} for `{}.{}`: {}.", value, name, slot, e)),
+            }
+        }
+
+        (theme, warnings)
+    }
+}
+
+/// Style for an ANSI code, e.g. `34`, `104` or `1`.
+fn ansi (code: u64) -> Result<Style, String> {
+    match code {
+        1 => Ok(Style::new().bold()),
+        3 => Ok(Style::new().italic()),
+        4 => Ok(Style::new().underline()),
+        7 => Ok(Style::new().reverse()),
+        30..=37 | 90..=97 => Ok(Style::ansi(code as u8)),
+        40..=47 | 100..=107 => Ok(Style::new().bg(Color::Ansi(code as u8 - 10))),
+        _ => Err(format!("{} isn't a supported ANSI code", code)),
+    }
+}
+
+/// Turns a slot value into a style. Values can be a hex color, an ANSI
+/// code or an object with `fg`, `bg`, `bold` & `italic`.
+fn style (value: &Value) -> Result<Style, String> {
+    match value {
+        Value::String(hex) => Ok(Style::new().fg(Color::hex(hex).map_err(|e| e.to_string())?)),
+        Value::Number(n) => ansi(n.as_u64().ok_or("expected a positive number")?),
+        Value::Object(parts) => {
+            let mut output = Style::new();
+
+            for (key, part) in parts {
+                match (key.as_str(), part) {
+                    ("fg", Value::String(hex)) => output = output.fg(Color::hex(hex).map_err(|e| e.to_string())?),
+                    ("bg", Value::String(hex)) => output = output.bg(Color::hex(hex).map_err(|e| e.to_string())?),
+                    ("bold", Value::Bool(bold)) => output.bold = *bold,
+                    ("italic", Value::Bool(italic)) => output.italic = *italic,
+                    _ => return Err(format!("unknown option `{}`", key)),
+                }
+            }
+
+            Ok(output)
+        },
+        _ => Err("expected a hex color, an ANSI code or an object".to_owned()),
+    }
+}
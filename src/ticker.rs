@@ -0,0 +1,42 @@
+// Frame timing that isn't affected by input
+use std::time::{Duration, Instant};
+
+pub struct Ticker {
+    /// When the next frame should be shown.
+    next: Instant,
+}
+
+impl Ticker {
+    pub fn new (interval: u32) -> Ticker {
+        Ticker { next: Instant::now() + Duration::from_millis(interval as u64) }
+    }
+
+    /// Time left until the next tick, used as the timeout for `event::poll()`.
+    pub fn remaining (&self) -> Duration {
+        self.next.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the next tick is due. The following tick is scheduled
+    /// `interval` after this one so that the timing doesn't drift.
+    pub fn due (&mut self, interval: u32) -> bool {
+        let now = Instant::now();
+
+        if now < self.next {
+            return false;
+        }
+
+        self.next += Duration::from_millis(interval as u64);
+
+        // Skip the ticks that were missed(e.g. while the terminal was suspended).
+        if self.next < now {
+            self.next = now + Duration::from_millis(interval as u64);
+        }
+
+        true
+    }
+
+    /// Starts counting from now.
+    pub fn reset (&mut self, interval: u32) {
+        self.next = Instant::now() + Duration::from_millis(interval as u64);
+    }
+}
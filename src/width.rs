@@ -0,0 +1,153 @@
+// Display width of text in terminal cells
+use std::sync::atomic::{AtomicUsize, Ordering};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Cells taken by Nerd Font icons(private use characters). Most fonts
+/// draw them in 1 cell, some in 2.
+static ICON_WIDTH: AtomicUsize = AtomicUsize::new(1);
+
+pub fn set_icon_width (width: usize) {
+    ICON_WIDTH.store(width.clamp(1, 2), Ordering::Relaxed);
+}
+
+fn is_private_use (c: char) -> bool {
+    matches!(c as u32, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD)
+}
+
+/// Cells taken by a single grapheme(e.g. `a`, `時`, `👩‍💻`).
+pub fn grapheme_width (grapheme: &str) -> usize {
+    if grapheme.chars().next().is_some_and(is_private_use) {
+        return ICON_WIDTH.load(Ordering::Relaxed);
+    }
+
+    // ZWJ sequences & emoji with modifiers are drawn as a single glyph.
+    grapheme.width().min(2)
+}
+
+/// Part of a text.
+pub enum Segment<'a> {
+    /// Escape sequences(e.g. colors in a spinner) take no space.
+    Escape(&'a str),
+    Grapheme(&'a str),
+}
+
+impl Segment<'_> {
+    pub fn text (&self) -> &str {
+        match self {
+            Segment::Escape(text) | Segment::Grapheme(text) => text,
+        }
+    }
+
+    /// Cells taken by the segment.
+    pub fn width (&self) -> usize {
+        match self {
+            Segment::Escape(_) => 0,
+            Segment::Grapheme(grapheme) => grapheme_width(grapheme),
+        }
+    }
+}
+
+pub fn segments (text: &str) -> Vec<Segment<'_>> {
+    let mut output = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("\x1b[") {
+        output.extend(rest[..start].graphemes(true).map(Segment::Grapheme));
+
+        let end = match rest[start + 2..].find(|c: char| ('@'..='~').contains(&c)) {
+            Some(end) => start + 2 + end + 1,
+            None => rest.len(),
+        };
+
+        output.push(Segment::Escape(&rest[start..end]));
+        rest = &rest[end..];
+    }
+
+    output.extend(rest.graphemes(true).map(Segment::Grapheme));
+    output
+}
+
+/// Cells taken by `text`.
+pub fn width (text: &str) -> usize {
+    segments(text).iter().map(Segment::width).sum()
+}
+
+/// Cuts `text` down to `width` cells, the end is replaced with `…` when
+/// the text is cut. Graphemes are never split.
+pub fn truncate (text: &str, width: usize) -> String {
+    if self::width(text) <= width {
+        return text.to_owned();
+    }
+
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut output = String::new();
+    let mut used = 0;
+
+    for segment in segments(text) {
+        let grapheme = match segment {
+            Segment::Escape(sequence) => {
+                output += sequence;
+                continue;
+            },
+            Segment::Grapheme(grapheme) => grapheme,
+        };
+        let size = grapheme_width(grapheme);
+
+        if used + size > width - 1 {
+            break;
+        }
+
+        output += grapheme;
+        used += size;
+    }
+
+    output + "…"
+}
+
+/// Where a frame goes when it's padded to the width of the widest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+/// Pads every frame with `fill` until it's as wide as the widest one, so
+/// the text after a spinner doesn't move.
+pub fn normalize (frames: &[String], align: Align, fill: char) -> Vec<String> {
+    let max_w = frames.iter().map(|f| width(f)).max().unwrap_or(0);
+    let fill_w = grapheme_width(&fill.to_string()).max(1);
+
+    // Cells a wide `fill` can't cover are filled with spaces.
+    let filler = |cells: usize| fill.to_string().repeat(cells / fill_w) + &" ".repeat(cells % fill_w);
+
+    frames.iter().map(|frame| {
+        let missing = max_w - width(frame);
+
+        match align {
+            Align::Left => frame.to_owned() + &filler(missing),
+            Align::Right => filler(missing) + frame,
+            Align::Center => filler(missing / 2) + frame + &filler(missing - missing / 2),
+        }
+    }).collect()
+}
+
+/// Pads `text` with spaces on the right until it takes `width` cells.
+pub fn pad (text: &str, width: usize) -> String {
+    text.to_owned() + &" ".repeat(width.saturating_sub(self::width(text)))
+}
+
+/// Padding needed on the left to center `text` in `width` cells.
+pub fn center_pad (text: &str, width: usize) -> usize {
+    width.saturating_sub(self::width(text)) / 2
+}
+
+/// Centers `text` in `width` cells.
+pub fn center (text: &str, width: usize) -> String {
+    pad(&(" ".repeat(center_pad(text, width)) + text), width)
+}